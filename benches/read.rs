@@ -0,0 +1,85 @@
+//! Benchmark reading a fixed-shape CSV grid straight into an `Array2` with
+//! `Array2::from_shape_vec`, against the old approach of collecting an `Array1` and reshaping it.
+//!
+//! Run with `cargo bench`.
+#[macro_use]
+extern crate criterion;
+extern crate csv;
+extern crate ndarray;
+extern crate ndarray_csv;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use csv::{Reader, ReaderBuilder};
+use ndarray::{Array1, Array2};
+use ndarray_csv::Array2Reader;
+use std::hint::black_box;
+
+fn grid(n_rows: usize, n_columns: usize) -> String {
+    let mut content = String::new();
+    for i in 0..n_rows {
+        let row: Vec<String> = (0..n_columns)
+            .map(|j| ((i * n_columns + j) as f64 / 7.0).to_string())
+            .collect();
+        content.push_str(&row.join(","));
+        content.push('\n');
+    }
+    content
+}
+
+fn reader(content: &str) -> Reader<&[u8]> {
+    ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(content.as_bytes())
+}
+
+/// How `deserialize_array2` used to work: collect every value into an `Array1`, then reshape it
+fn read_via_array1(content: &str, shape: (usize, usize)) -> Array2<f64> {
+    let values: Array1<f64> = reader(content)
+        .deserialize::<Vec<f64>>()
+        .flat_map(|row| row.unwrap())
+        .collect();
+    #[allow(deprecated)]
+    values.into_shape(shape).unwrap()
+}
+
+fn bench_read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read");
+    for &n_rows in [100, 10_000].iter() {
+        let shape = (n_rows, 100);
+        let content = grid(shape.0, shape.1);
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("array1_into_shape", n_rows),
+            &content,
+            |b, content| b.iter(|| read_via_array1(black_box(content), shape)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("deserialize_array2", n_rows),
+            &content,
+            |b, content| {
+                b.iter(|| {
+                    let array: Array2<f64> = reader(black_box(content))
+                        .deserialize_array2(shape)
+                        .unwrap();
+                    array
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("deserialize_array2_fast", n_rows),
+            &content,
+            |b, content| {
+                b.iter(|| {
+                    let array: Array2<f64> = reader(black_box(content))
+                        .deserialize_array2_fast(shape)
+                        .unwrap();
+                    array
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_read);
+criterion_main!(benches);
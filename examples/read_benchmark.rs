@@ -0,0 +1,91 @@
+//! Time reading large in-memory CSVs with `deserialize_array2`, `deserialize_array2_fast` and
+//! `deserialize_array2_u8`.
+//!
+//! Run with `cargo run --release --example read_benchmark`.
+extern crate csv;
+extern crate ndarray;
+extern crate ndarray_csv;
+
+use csv::ReaderBuilder;
+use ndarray::Array2;
+use ndarray_csv::Array2Reader;
+use std::io::Cursor;
+use std::time::Instant;
+
+fn grid<T: ToString, F: Fn(usize) -> T>(n_rows: usize, n_columns: usize, cell: F) -> String {
+    let mut content = String::new();
+    for i in 0..n_rows {
+        let row: Vec<String> = (0..n_columns)
+            .map(|j| cell(i * n_columns + j).to_string())
+            .collect();
+        content.push_str(&row.join(","));
+        content.push('\n');
+    }
+    content
+}
+
+fn reader(content: &str) -> csv::Reader<Cursor<&[u8]>> {
+    ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(Cursor::new(content.as_bytes()))
+}
+
+fn main() {
+    let (n_rows, n_columns) = (10_000, 100);
+    let content = grid(n_rows, n_columns, |i| i as f64 / 7.0);
+
+    let start = Instant::now();
+    let array: Array2<f64> = reader(&content)
+        .deserialize_array2((n_rows, n_columns))
+        .unwrap();
+    println!(
+        "deserialize_array2 read a {}x{} array from {} bytes in {:?}",
+        array.nrows(),
+        array.ncols(),
+        content.len(),
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let array_fast: Array2<f64> = reader(&content)
+        .deserialize_array2_fast((n_rows, n_columns))
+        .unwrap();
+    println!(
+        "deserialize_array2_fast read the same array in {:?}",
+        start.elapsed()
+    );
+    assert_eq!(array_fast, array);
+
+    let content = grid(n_rows, n_columns, |i| i % 256);
+    let start = Instant::now();
+    let bytes: Array2<u8> = reader(&content)
+        .deserialize_array2((n_rows, n_columns))
+        .unwrap();
+    println!(
+        "deserialize_array2 read a {}x{} u8 array from {} bytes in {:?}",
+        bytes.nrows(),
+        bytes.ncols(),
+        content.len(),
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let bytes_fast: Array2<u8> = reader(&content)
+        .deserialize_array2_fast((n_rows, n_columns))
+        .unwrap();
+    println!(
+        "deserialize_array2_fast read the same array in {:?}",
+        start.elapsed()
+    );
+    assert_eq!(bytes_fast, bytes);
+
+    let start = Instant::now();
+    let bytes_u8 = reader(&content)
+        .deserialize_array2_u8((n_rows, n_columns))
+        .unwrap();
+    println!(
+        "deserialize_array2_u8 read the same array in {:?}",
+        start.elapsed()
+    );
+    assert_eq!(bytes_u8, bytes);
+}
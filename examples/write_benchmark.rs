@@ -0,0 +1,43 @@
+//! Time writing a large array into an in-memory CSV with `serialize_array2` and
+//! `serialize_array2_slice`.
+//!
+//! Run with `cargo run --release --example write_benchmark`.
+extern crate csv;
+extern crate ndarray;
+extern crate ndarray_csv;
+
+use csv::WriterBuilder;
+use ndarray::Array2;
+use ndarray_csv::Array2Writer;
+use std::time::Instant;
+
+fn main() {
+    let (n_rows, n_columns) = (10_000, 100);
+    let array = Array2::from_shape_fn((n_rows, n_columns), |(i, j)| {
+        (i * n_columns + j) as f64 / 7.0
+    });
+
+    let start = Instant::now();
+    let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+    writer.serialize_array2(&array).unwrap();
+    let bytes = writer.into_inner().unwrap();
+    println!(
+        "serialize_array2 wrote a {}x{} array into {} bytes in {:?}",
+        n_rows,
+        n_columns,
+        bytes.len(),
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+    writer
+        .serialize_array2_slice(array.as_slice().unwrap(), n_columns)
+        .unwrap();
+    let bytes_slice = writer.into_inner().unwrap();
+    println!(
+        "serialize_array2_slice wrote the same array in {:?}",
+        start.elapsed()
+    );
+    assert_eq!(bytes_slice, bytes);
+}
@@ -33,61 +33,638 @@
 //! }
 //! ```
 //!
+//! For the common case of a headerless CSV file, `read_array2_from_path` and `write_array2_to_path`
+//! take care of opening the file and configuring the reader or writer.
+//!
 //! This project uses [cargo-make](https://sagiegurari.github.io/cargo-make/) for builds; to build,
 //! run `cargo make all`.
 //!
 //! To prevent denial-of-service attacks, do not read in untrusted CSV streams of unbounded length;
-//! this can be implemented with `std::io::Read::take`.
+//! this can be implemented with `std::io::Read::take`, or by capping the number of rows with
+//! `Array2Reader::deserialize_array2_dynamic_capped` or of columns with
+//! `Array2Reader::deserialize_array2_dynamic_max_cols`.
+
+// `usize::is_multiple_of` needs Rust 1.87, which is newer than this crate otherwise requires
+#![allow(unknown_lints, clippy::manual_is_multiple_of)]
+
 extern crate csv;
-extern crate either;
+#[cfg(feature = "flate2")]
+extern crate flate2;
+#[cfg(feature = "memmap2")]
+extern crate memmap2;
+#[cfg(feature = "num-complex")]
+extern crate num_complex;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "ryu")]
+extern crate ryu;
 #[cfg(test)]
 #[macro_use]
 extern crate matches;
-#[cfg_attr(test, macro_use(array))]
+#[cfg_attr(test, macro_use(array, s))]
 extern crate ndarray;
 extern crate serde;
 
-use csv::{Reader, Writer};
-use either::Either;
+use csv::{ByteRecord, Position, Reader, ReaderBuilder, StringRecord, Writer, WriterBuilder};
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "flate2")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "flate2")]
+use flate2::Compression;
+#[cfg(feature = "memmap2")]
+use memmap2::Mmap;
 use ndarray::iter::Iter;
-use ndarray::{Array1, Array2, Dim};
+use ndarray::{
+    Array1, Array2, ArrayBase, ArrayD, ArrayView1, Axis, Data, Dim, Ix1, Ix2, IxDyn, Order,
+    ShapeBuilder,
+};
+#[cfg(feature = "num-complex")]
+use num_complex::Complex;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use serde::{Serialize, Serializer};
-use std::cell::Cell;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::io::{Read, Write};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::iter::once;
+use std::marker::PhantomData;
+use std::mem::discriminant;
+use std::num::IntErrorKind;
+use std::ops::Range;
+use std::path::Path;
+use std::str::{self, FromStr};
+use std::string::FromUtf8Error;
+use std::sync::Arc;
 
-/// An extension trait; this is implemented by `&mut csv::Reader`
+/// An extension trait; this is implemented by `&mut csv::Reader` and by `csv::Reader`
+///
+/// Calling a method on an owned `csv::Reader` consumes it. To keep using the reader afterwards,
+/// call the method on `&mut reader` instead.
 pub trait Array2Reader {
     /// Read CSV data into a new ndarray with the given shape
+    ///
+    /// To allow missing values, read into an array of `Option<A>`: empty fields become `None`.
+    ///
+    /// Fields are parsed exactly as the reader returns them, so a reader built with
+    /// `csv::ReaderBuilder::trim` has its whitespace trimmed before parsing.
+    ///
+    /// If `A` is a tuple type, each element is read from as many consecutive fields as the tuple has
+    /// items, and `shape` counts elements rather than fields.
+    ///
+    /// For `f32` and `f64`, besides ordinary numbers, the tokens `nan`, `inf` and `infinity` are
+    /// accepted case-insensitively, with an optional leading `+` or `-`. This covers the spellings
+    /// written by e.g. NumPy (`nan`, `inf`), R (`NaN`, `Inf`) and JavaScript (`Infinity`). Other
+    /// tokens, such as R's `NA`, are `Parse` errors.
     fn deserialize_array2<A: DeserializeOwned>(
         self,
         shape: (usize, usize),
     ) -> Result<Array2<A>, ReadError>;
 
+    /// Read CSV data into a new ndarray, inferring its shape
+    ///
+    /// The number of columns is the width of the first row, and every later row must have the same
+    /// width. So if a row doesn't, the `expected` width in the `NColumns` error is always the first
+    /// row's width.
+    ///
+    /// Input with no records at all, e.g. an empty file or one with only a header row, is an
+    /// `Empty` error, since there is no row to infer the number of columns from. To read it as an
+    /// empty array instead, use `deserialize_array2_dynamic_or_empty`.
+    ///
+    /// Like every method here, this reads the records that the `csv::Reader` returns, so comment
+    /// lines skipped by a reader built with `csv::ReaderBuilder::comment` aren't rows: they're not
+    /// in the array and don't count towards the row indices in errors.
     fn deserialize_array2_dynamic<A: DeserializeOwned>(self) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray, inferring its shape, laid out in memory in the given order
+    ///
+    /// The logical position of each value is the same as with `deserialize_array2_dynamic`; only
+    /// the memory layout depends on `order`.
+    fn deserialize_array2_dynamic_with_order<A: DeserializeOwned>(
+        self,
+        order: Order,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read `total_elements` values of CSV data into a new ndarray, inferring the number of columns
+    /// from the first row, e.g. when the number of values is known from metadata but the layout
+    /// isn't
+    ///
+    /// If `total_elements` isn't a whole number of rows of the first row's width, this is a
+    /// `TotalElements` error, and if the data runs out first, it is an `NRows` error. No records
+    /// are read after the last row that's needed. A `total_elements` of 0 gives a `(0, 0)` array
+    /// without reading anything.
+    fn deserialize_array2_total<A: DeserializeOwned>(
+        self,
+        total_elements: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray, inferring its shape, dropping the empty last field of any
+    /// row that ends with one, e.g. because a tool wrote a delimiter after every field
+    ///
+    /// A `Warning` is returned along with the array for each field that was dropped, so that the
+    /// problem is reported without the read failing. Rows with a single field are left alone. If
+    /// only some rows have a trailing empty field, the reader must be configured with
+    /// `flexible(true)`.
+    fn deserialize_array2_dynamic_checked<A: DeserializeOwned>(
+        self,
+    ) -> Result<(Array2<A>, Vec<Warning>), ReadError>;
+
+    /// Read CSV data into a new ndarray, inferring its shape, or into a `(0, 0)` array if there are
+    /// no records
+    fn deserialize_array2_dynamic_or_empty<A: DeserializeOwned>(
+        self,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray, inferring its shape exactly like
+    /// `deserialize_array2_dynamic`, and also return a `ShapeReport` describing the rows
+    fn deserialize_array2_dynamic_report<A: DeserializeOwned>(
+        self,
+    ) -> Result<(Array2<A>, ShapeReport), ReadError>;
+
+    /// Read CSV data where every row has exactly `n_columns` columns into a new ndarray, inferring
+    /// the number of rows
+    ///
+    /// This is stricter than `deserialize_array2_dynamic`, which only checks that every row is as
+    /// wide as the first one.
+    fn deserialize_array2_rows_dynamic<A: DeserializeOwned>(
+        self,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new square ndarray, inferring its size, e.g. for an adjacency or
+    /// covariance matrix
+    ///
+    /// Data with a different number of rows than columns is a `NotSquare` error.
+    fn deserialize_array2_square<A: DeserializeOwned>(self) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray, inferring its shape, but fail with `TooManyRows` as soon as
+    /// more than `max_rows` rows have been read
+    ///
+    /// This bounds the memory used when reading untrusted data.
+    fn deserialize_array2_dynamic_capped<A: DeserializeOwned>(
+        self,
+        max_rows: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray, inferring its shape, but fail with `TooManyColumns` as
+    /// soon as a row has more than `max_columns` fields
+    ///
+    /// The row's fields are counted before any of them are parsed. Note that the `csv::Reader` has
+    /// already buffered the whole row by then, so to bound memory completely, also limit the input
+    /// with `std::io::Read::take`.
+    fn deserialize_array2_dynamic_max_cols<A: DeserializeOwned>(
+        self,
+        max_columns: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray, inferring its shape, stopping at the first error
+    ///
+    /// Unlike `deserialize_array2_dynamic`, the rows that were read successfully before the error
+    /// are returned along with it, so that they can be inspected or salvaged. The array only
+    /// contains complete rows.
+    fn deserialize_array2_dynamic_partial<A: DeserializeOwned>(
+        self,
+    ) -> (Array2<A>, Option<ReadError>);
+
+    /// Read CSV data into a new ndarray, inferring its shape, and also return the inferred
+    /// `(rows, columns)` shape
+    ///
+    /// A trailing delimiter at the end of a row counts as an extra, empty column.
+    fn deserialize_array2_with_shape<A: DeserializeOwned>(
+        self,
+    ) -> Result<(Array2<A>, (usize, usize)), ReadError>;
+
+    /// Read CSV data with the given number of columns into a new ndarray, inferring the number of
+    /// rows. Rows shorter than `n_columns` are padded with `A::default()`.
+    ///
+    /// Rows longer than `n_columns` are still an error. The reader must be configured with
+    /// `flexible(true)`, otherwise `csv` itself rejects rows of differing lengths.
+    fn deserialize_array2_padded<A: DeserializeOwned + Default>(
+        self,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read a header row followed by CSV data with the given number of columns, inferring the
+    /// number of rows
+    ///
+    /// If the reader was configured with `has_headers(true)`, its headers are used, otherwise the
+    /// first record is read as the header row. Two headers with the same name are a
+    /// `DuplicateHeader` error.
+    fn deserialize_array2_with_headers<A: DeserializeOwned>(
+        self,
+        n_columns: usize,
+    ) -> Result<(Vec<String>, Array2<A>), ReadError>;
+
+    /// Read only the given columns of CSV data into a new ndarray with `n_rows` rows
+    ///
+    /// The output columns are in the order given by `columns`, which doesn't have to be sorted: with
+    /// `&[2, 0, 1]`, output column 0 is column 2 of the data. Columns that aren't selected are
+    /// never parsed, so they don't need to be valid values of `A`.
+    fn deserialize_array2_columns<A: DeserializeOwned>(
+        self,
+        columns: &[usize],
+        n_rows: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Discard the first `skip_rows` records without parsing them, then read the rest of the CSV
+    /// data into a new ndarray with the given shape
+    ///
+    /// The skipped rows may have any number of columns, but then the reader must be configured
+    /// with `flexible(true)`. Row indices in errors count from the first row after the skipped
+    /// ones.
+    fn deserialize_array2_skip<A: DeserializeOwned>(
+        self,
+        skip_rows: usize,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read at most the first `k` rows of CSV data, each of which must have `n_columns` columns,
+    /// into a new ndarray, e.g. to preview a large file
+    ///
+    /// The array has fewer than `k` rows if the data runs out first. No records after the first
+    /// `k` are read, so called on `&mut reader`, the reader can carry on from where this stopped.
+    fn deserialize_array2_head<A: DeserializeOwned>(
+        self,
+        k: usize,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read the last `k` rows of CSV data, each of which must have `n_columns` columns, into a new
+    /// ndarray, e.g. to get the latest entries of an append-only log
+    ///
+    /// Every row is read and checked, but only the most recent `k` are kept, so memory use is
+    /// proportional to `k` rather than to the size of the data. The array has fewer than `k` rows
+    /// if the data does.
+    fn deserialize_array2_tail<A: DeserializeOwned>(
+        self,
+        k: usize,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data whose first record is a shape header such as `2,3`, giving the number of rows
+    /// and columns, into a new ndarray with that shape
+    ///
+    /// Any fields after the first two in the header must be empty, so the header can be padded to
+    /// the width of the rows; otherwise, the reader must be configured with `flexible(true)`. For
+    /// data with a single column, the header can instead be a single field such as `2x1`.
+    ///
+    /// A header field that isn't a valid size is a `Parse` error on row 0, and a shape whose number
+    /// of elements overflows `usize` is a `ShapeOverflow` error. The header isn't trusted to
+    /// preallocate the array, so a shape that is merely far too large is an `NRows` or `NColumns`
+    /// error once the data runs out. As with `deserialize_array2_skip`, row indices in errors in
+    /// the data count from the first row after the header.
+    fn deserialize_array2_with_shape_header<A: DeserializeOwned>(
+        self,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, passing the text of each field through
+    /// `f` before parsing it, e.g. to turn a locale-specific number such as `1.234,56` into
+    /// `1234.56`
+    ///
+    /// `f` sees the raw text of each field, and the value is parsed from the text that it returns;
+    /// a `Parse` error contains the returned text.
+    fn deserialize_array2_map_cells<A: DeserializeOwned, F: FnMut(&str) -> Cow<str>>(
+        self,
+        shape: (usize, usize),
+        f: F,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, calling `on_progress` with the number
+    /// of rows read so far after every `every` rows, e.g. to drive a progress bar
+    ///
+    /// If the read succeeds, `on_progress` is called exactly once with the final number of rows,
+    /// whether or not that is a multiple of `every`. An `every` of 0 only reports the final count.
+    fn deserialize_array2_with_progress<A: DeserializeOwned, F: FnMut(usize)>(
+        self,
+        shape: (usize, usize),
+        every: usize,
+        on_progress: F,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, trimming leading and trailing
+    /// whitespace from each field before parsing it
+    ///
+    /// This is like building the reader with `csv::Trim::Fields`, except that the headers are left
+    /// alone.
+    fn deserialize_array2_trimmed<A: DeserializeOwned>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new boolean ndarray with the given shape, where fields equal to one of
+    /// `truthy` are `true` and fields equal to one of `falsy` are `false`, e.g. `&["Y"]` and
+    /// `&["N"]`
+    ///
+    /// Any other field is a `Parse` error.
+    fn deserialize_array2_bool(
+        self,
+        shape: (usize, usize),
+        truthy: &[&str],
+        falsy: &[&str],
+    ) -> Result<Array2<bool>, ReadError>;
+
+    /// Read CSV data into a new ndarray of characters with the given shape, e.g. a grid of DNA bases
+    ///
+    /// Each field must be exactly one `char`; any other field, including an empty one, is a
+    /// `Parse` error.
+    fn deserialize_array2_chars(self, shape: (usize, usize)) -> Result<Array2<char>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, where fields equal to `sentinel`,
+    /// e.g. `"-999"` or `"NA"`, become `replacement` and other fields are parsed as usual
+    fn deserialize_array2_with_sentinel<A: DeserializeOwned + Clone>(
+        self,
+        shape: (usize, usize),
+        sentinel: &str,
+        replacement: A,
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, putting `fill` in place of each field
+    /// that fails to parse, and return the `(row, column)` index of each such field along with the
+    /// array
+    ///
+    /// Only `Parse` and `OutOfRange` errors are replaced; any other error, such as data of the
+    /// wrong shape, is still returned.
+    fn deserialize_array2_lossy<A: DeserializeOwned + Clone>(
+        self,
+        shape: (usize, usize),
+        fill: A,
+    ) -> Result<(Array2<A>, Vec<CellIndex>), ReadError>;
+
+    /// Read CSV data where the first field of each row is a label of type `I`, followed by
+    /// `n_columns` fields of type `A`, inferring the number of rows
+    ///
+    /// The labels are returned separately from the array of values.
+    fn deserialize_array2_with_index<I: DeserializeOwned, A: DeserializeOwned>(
+        self,
+        n_columns: usize,
+    ) -> Result<(Vec<I>, Array2<A>), ReadError>;
+
+    /// Read CSV data where the first `key_columns` fields of each row make up a key of type `K`,
+    /// followed by `n_value_columns` fields of type `A`, inferring the number of rows
+    ///
+    /// The key fields are deserialized by position, so `K` can be a tuple or a struct with one
+    /// field per key column, e.g. a date and a site ID. The keys are returned separately from the
+    /// array of values. Column indices in errors count the key columns.
+    fn deserialize_array2_split<K: DeserializeOwned, A: DeserializeOwned>(
+        self,
+        key_columns: usize,
+        n_value_columns: usize,
+    ) -> Result<(Vec<K>, Array2<A>), ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, laid out in column-major (Fortran)
+    /// order
+    ///
+    /// The logical position of each value is the same as with `deserialize_array2`; only the memory
+    /// layout is different.
+    fn deserialize_array2_f_order<A: DeserializeOwned>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data in which each row is a column of the logical array, returning an ndarray with
+    /// shape `logical_shape`
+    ///
+    /// The CSV data must have `logical_shape.1` rows of `logical_shape.0` columns; errors such as
+    /// `NRows` and `NColumns` describe the CSV data as written, before it is transposed. The values
+    /// aren't copied to transpose them, so the array is laid out in column-major (Fortran) order.
+    fn deserialize_array2_transposed<A: DeserializeOwned>(
+        self,
+        logical_shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data whose rows may have different lengths into one new 1D ndarray per row, in
+    /// order, e.g. for an adjacency list
+    ///
+    /// The number of columns is never checked, but the reader must be built with
+    /// `csv::ReaderBuilder::flexible(true)` for it to accept rows of different lengths.
+    fn deserialize_ragged<A: DeserializeOwned>(self) -> Result<Vec<Array1<A>>, ReadError>;
+
+    /// Read CSV data with the given shape into one new, contiguous 1D ndarray per column
+    ///
+    /// The values are collected column by column as they are read, so the data isn't held as a 2D
+    /// array first.
+    fn deserialize_columns<A: DeserializeOwned>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Vec<Array1<A>>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, parsing each field with `FromStr`
+    /// instead of serde
+    ///
+    /// This skips serde and the per-row `Vec` allocation, parsing each field straight into the
+    /// array's buffer. How much time that saves depends on how expensive parsing `A` is; the
+    /// `read_benchmark` example compares the two. Fields that fail to parse are reported as
+    /// `ReadError::Parse`.
+    ///
+    /// Since serde isn't involved, this also reads element types that implement `FromStr` but not
+    /// `Deserialize`, such as custom numeric wrappers.
+    fn deserialize_array2_fast<A: FromStr>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into a new ndarray of bytes with the given shape, e.g. a grid of pixel values
+    ///
+    /// This is a specialized fast path: each field is parsed directly from the record's bytes with
+    /// a dedicated parser, which only accepts decimal digits. A number above 255 is an
+    /// `OutOfRange` error, and anything else, including an empty field, a sign or whitespace, is a
+    /// `Parse` error. The `read_benchmark` example compares it with the other readers.
+    fn deserialize_array2_u8(self, shape: (usize, usize)) -> Result<Array2<u8>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, coercing every field to `A` with its
+    /// `FromStr` implementation
+    ///
+    /// This is for columns written with different types that should share one element type, e.g.
+    /// an integer id column alongside float columns: with `A = f64`, `"42"` becomes `42.0`. The
+    /// other way around, a field that `A::from_str` rejects but that is a whole number written with
+    /// a decimal point, such as `"1.0"` or `"42.00"`, is parsed again without its fractional part,
+    /// so integer types accept it losslessly. Any other field that `A::from_str` rejects, e.g.
+    /// `"1.5"` for an integer type, is a `Parse` error.
+    fn deserialize_array2_coerce<A: FromStr>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data in which each complex number is stored as two adjacent columns, the real part
+    /// followed by the imaginary part, into a new complex ndarray with the given shape
+    ///
+    /// Each row must have twice as many fields as `shape_in_complex` has columns; otherwise, the
+    /// `NColumns` error gives the expected number of fields. This requires the `num-complex`
+    /// feature.
+    #[cfg(feature = "num-complex")]
+    fn deserialize_array2_complex<A: DeserializeOwned>(
+        self,
+        shape_in_complex: (usize, usize),
+    ) -> Result<Array2<Complex<A>>, ReadError>;
+
+    /// Read CSV data into a new ndarray with the given shape, parsing the fields on multiple
+    /// threads
+    ///
+    /// All records are read into memory first, and then parsed in parallel. This only helps when
+    /// parsing the fields takes longer than reading the input. If more than one row fails to
+    /// parse, the error for the first of them is returned. This requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    fn deserialize_array2_par<A: DeserializeOwned + Send>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError>;
+
+    /// Read CSV data into an existing ndarray, whose shape is the expected shape of the data
+    ///
+    /// This lets a single buffer be reused across many reads. If an error is returned, `out` may
+    /// have been partially overwritten.
+    fn deserialize_array2_into<A: DeserializeOwned>(
+        self,
+        out: &mut Array2<A>,
+    ) -> Result<(), ReadError>;
+
+    /// Check that the CSV data has the given shape without parsing any fields or building an array
+    ///
+    /// The errors are the same `NRows` and `NColumns` errors that `deserialize_array2` would return
+    /// for the same data, so this can be used as a cheap preflight check before a large read.
+    fn validate_shape(self, expected: (usize, usize)) -> Result<(), ReadError>;
+
+    /// Count how many rows of the CSV data have each number of columns, without parsing any fields
+    /// or building an array, e.g. to work out the right shape for a messy file
+    ///
+    /// Rows are counted as they are read, and at most `max_rows` of them are read. The reader must
+    /// be configured with `flexible(true)` to get past the first row with a different number of
+    /// columns.
+    fn analyze_columns(self, max_rows: usize) -> Result<ColumnHistogram, ReadError>;
 }
 
-#[derive(Debug)]
+/// How many rows of CSV data have each number of columns, from `Array2Reader::analyze_columns`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ColumnHistogram {
+    /// The number of rows with each number of columns, by number of columns
+    pub counts: BTreeMap<usize, usize>,
+    pub n_rows: usize,
+    /// The index of the first row with the most columns, if there were any rows
+    pub widest_row: Option<usize>,
+    /// Whether there were more rows after the first `max_rows`, which weren't counted
+    pub truncated: bool,
+}
+
+impl ColumnHistogram {
+    /// The most columns that any row has, or 0 if there were no rows
+    pub fn max_columns(&self) -> usize {
+        self.counts.keys().next_back().cloned().unwrap_or(0)
+    }
+}
+
+/// The `(row, column)` index of a field in CSV data
+pub type CellIndex = (usize, usize);
+
+/// An error while reading CSV data into an ndarray
+///
+/// This is `Clone`, e.g. so that errors from a batch of files can be collected and deduplicated.
+/// Since `csv::Error` and `std::io::Error` aren't `Clone`, the `Csv` and `Io` variants hold them in
+/// an `Arc`. Cloning one of these errors shares the underlying error rather than copying it, but it
+/// keeps all of its information, including its kind and source. To take ownership of the
+/// underlying error, use `Arc::try_unwrap`.
+#[derive(Debug, Clone)]
 pub enum ReadError {
-    Csv(csv::Error),
+    /// The underlying CSV reader failed while reading the row at `at_row_index`
+    Csv {
+        source: Arc<csv::Error>,
+        at_row_index: usize,
+    },
     NRows {
         expected: usize,
         actual: usize,
     },
+    /// A row doesn't have the expected number of columns. `line` is the line of the CSV data
+    /// that the row starts on, as counted by the `csv::Reader`, if known. The reader leaves
+    /// comment lines out of this count.
     NColumns {
         at_row_index: usize,
         expected: usize,
         actual: usize,
+        line: Option<u64>,
+    },
+    /// The CSV data was supposed to be read as a 1D array, but it has multiple rows and multiple
+    /// columns
+    NotOneDimensional {
+        n_rows: usize,
+        n_columns: usize,
+    },
+    /// The CSV data has more than the maximum number of rows that the caller allowed
+    TooManyRows {
+        max: usize,
+    },
+    /// The row at `at_row_index` has more than the maximum number of columns that the caller
+    /// allowed
+    TooManyColumns {
+        at_row_index: usize,
+        max: usize,
+    },
+    /// The header row doesn't have the expected number of columns
+    NHeaders {
+        expected: usize,
+        actual: usize,
+    },
+    /// More than one column of the header row has the name `name`
+    DuplicateHeader {
+        name: String,
+    },
+    /// The CSV file could not be opened
+    Io(Arc<std::io::Error>),
+    /// A column was requested that is past the end of a row
+    NoSuchColumn {
+        at_row_index: usize,
+        column: usize,
+        n_columns: usize,
+    },
+    /// A field could not be parsed into the array's element type. `value` is the field's text.
+    Parse {
+        at_row_index: usize,
+        at_col_index: usize,
+        value: String,
+    },
+    /// A field is an integer that is too large or too small for the array's integer element type,
+    /// e.g. `300` for `u8`. `value` is the field's text.
+    ///
+    /// The serde-based readers and `Array2Reader::deserialize_array2_u8` report this; readers that
+    /// parse with `FromStr` report a `Parse` error instead.
+    OutOfRange {
+        at_row_index: usize,
+        at_col_index: usize,
+        value: String,
+    },
+    /// The number of values that was asked for isn't a whole number of rows of `n_columns` columns
+    TotalElements {
+        total_elements: usize,
+        n_columns: usize,
+    },
+    /// A shape header, or the shape of an N-dimensional array, has more elements than can be
+    /// counted, so no array can have it
+    ///
+    /// For an N-dimensional shape, `n_columns` is the length of the axis that overflowed and
+    /// `n_rows` is the product of the lengths before it.
+    ShapeOverflow {
+        n_rows: usize,
+        n_columns: usize,
+    },
+    /// There were no records to infer the shape of the data from
+    Empty,
+    /// The data was expected to have as many rows as columns
+    NotSquare {
+        rows: usize,
+        cols: usize,
     },
 }
 
 impl Display for ReadError {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         match self {
-            ReadError::Csv(csv_error) => csv_error.fmt(f),
+            ReadError::Csv {
+                source,
+                at_row_index,
+            } => write!(f, "On row {}, {}", at_row_index, source),
             ReadError::NRows { expected, actual } => {
                 write!(f, "Expected {} rows but got {} rows", expected, actual)
             }
@@ -95,150 +672,3419 @@ impl Display for ReadError {
                 at_row_index,
                 expected,
                 actual,
+                line,
+            } => {
+                write!(f, "On row {}", at_row_index)?;
+                if let Some(line) = line {
+                    write!(f, " (line {})", line)?;
+                }
+                write!(
+                    f,
+                    ", expected {} columns but got {} columns",
+                    expected, actual
+                )
+            }
+            ReadError::NotOneDimensional { n_rows, n_columns } => write!(
+                f,
+                "Expected a single row or a single column but got {} rows and {} columns",
+                n_rows, n_columns
+            ),
+            ReadError::TooManyRows { max } => write!(f, "Expected at most {} rows", max),
+            ReadError::TooManyColumns { at_row_index, max } => write!(
+                f,
+                "On row {}, expected at most {} columns",
+                at_row_index, max
+            ),
+            ReadError::NHeaders { expected, actual } => write!(
+                f,
+                "Expected {} headers but got {} headers",
+                expected, actual
+            ),
+            ReadError::DuplicateHeader { name } => {
+                write!(f, "More than one column has the header {:?}", name)
+            }
+            ReadError::Io(io_error) => io_error.fmt(f),
+            ReadError::NoSuchColumn {
+                at_row_index,
+                column,
+                n_columns,
+            } => write!(
+                f,
+                "On row {}, there is no column {} because the row has {} columns",
+                at_row_index, column, n_columns
+            ),
+            ReadError::Parse {
+                at_row_index,
+                at_col_index,
+                value,
+            } => write!(
+                f,
+                "On row {}, column {}, could not parse {:?}",
+                at_row_index, at_col_index, value
+            ),
+            ReadError::OutOfRange {
+                at_row_index,
+                at_col_index,
+                value,
+            } => write!(
+                f,
+                "On row {}, column {}, the value {} is out of range for the element type",
+                at_row_index, at_col_index, value
+            ),
+            ReadError::TotalElements {
+                total_elements,
+                n_columns,
             } => write!(
                 f,
-                "On row {}, expected {} columns but got {} columns",
-                at_row_index, expected, actual
+                "Expected {} values, which isn't a whole number of rows of {} columns",
+                total_elements, n_columns
+            ),
+            ReadError::ShapeOverflow { n_rows, n_columns } => write!(
+                f,
+                "The shape header gives {} rows and {} columns, which is too many elements",
+                n_rows, n_columns
+            ),
+            ReadError::Empty => write!(f, "The CSV data has no records"),
+            ReadError::NotSquare { rows, cols } => write!(
+                f,
+                "Expected a square matrix but got {} rows and {} columns",
+                rows, cols
+            ),
+        }
+    }
+}
+
+/// Errors compare equal when they are the same variant with the same fields. Wrapped `csv::Error`s
+/// and `std::io::Error`s don't implement `PartialEq`, so they are compared by their kind only: two
+/// `Csv` errors are equal if they occurred on the same row and their `csv::ErrorKind`s are the same
+/// variant, and two `Io` errors are equal if they have the same `std::io::ErrorKind`.
+impl PartialEq for ReadError {
+    fn eq(&self, other: &Self) -> bool {
+        use ReadError::*;
+        match (self, other) {
+            (
+                Csv {
+                    source,
+                    at_row_index,
+                },
+                Csv {
+                    source: other_source,
+                    at_row_index: other_at_row_index,
+                },
+            ) => {
+                at_row_index == other_at_row_index
+                    && discriminant(source.kind()) == discriminant(other_source.kind())
+            }
+            (
+                NRows { expected, actual },
+                NRows {
+                    expected: other_expected,
+                    actual: other_actual,
+                },
+            ) => expected == other_expected && actual == other_actual,
+            (
+                NColumns {
+                    at_row_index,
+                    expected,
+                    actual,
+                    line,
+                },
+                NColumns {
+                    at_row_index: other_at_row_index,
+                    expected: other_expected,
+                    actual: other_actual,
+                    line: other_line,
+                },
+            ) => {
+                at_row_index == other_at_row_index
+                    && expected == other_expected
+                    && actual == other_actual
+                    && line == other_line
+            }
+            (
+                NotOneDimensional { n_rows, n_columns },
+                NotOneDimensional {
+                    n_rows: other_n_rows,
+                    n_columns: other_n_columns,
+                },
+            ) => n_rows == other_n_rows && n_columns == other_n_columns,
+            (TooManyRows { max }, TooManyRows { max: other_max }) => max == other_max,
+            (
+                TooManyColumns { at_row_index, max },
+                TooManyColumns {
+                    at_row_index: other_at_row_index,
+                    max: other_max,
+                },
+            ) => at_row_index == other_at_row_index && max == other_max,
+            (
+                NHeaders { expected, actual },
+                NHeaders {
+                    expected: other_expected,
+                    actual: other_actual,
+                },
+            ) => expected == other_expected && actual == other_actual,
+            (DuplicateHeader { name }, DuplicateHeader { name: other_name }) => name == other_name,
+            (Io(io_error), Io(other_io_error)) => io_error.kind() == other_io_error.kind(),
+            (
+                NoSuchColumn {
+                    at_row_index,
+                    column,
+                    n_columns,
+                },
+                NoSuchColumn {
+                    at_row_index: other_at_row_index,
+                    column: other_column,
+                    n_columns: other_n_columns,
+                },
+            ) => {
+                at_row_index == other_at_row_index
+                    && column == other_column
+                    && n_columns == other_n_columns
+            }
+            (
+                Parse {
+                    at_row_index,
+                    at_col_index,
+                    value,
+                },
+                Parse {
+                    at_row_index: other_at_row_index,
+                    at_col_index: other_at_col_index,
+                    value: other_value,
+                },
+            ) => {
+                at_row_index == other_at_row_index
+                    && at_col_index == other_at_col_index
+                    && value == other_value
+            }
+            (
+                OutOfRange {
+                    at_row_index,
+                    at_col_index,
+                    value,
+                },
+                OutOfRange {
+                    at_row_index: other_at_row_index,
+                    at_col_index: other_at_col_index,
+                    value: other_value,
+                },
+            ) => {
+                at_row_index == other_at_row_index
+                    && at_col_index == other_at_col_index
+                    && value == other_value
+            }
+            (
+                TotalElements {
+                    total_elements,
+                    n_columns,
+                },
+                TotalElements {
+                    total_elements: other_total_elements,
+                    n_columns: other_n_columns,
+                },
+            ) => total_elements == other_total_elements && n_columns == other_n_columns,
+            (
+                ShapeOverflow { n_rows, n_columns },
+                ShapeOverflow {
+                    n_rows: other_n_rows,
+                    n_columns: other_n_columns,
+                },
+            ) => n_rows == other_n_rows && n_columns == other_n_columns,
+            (Empty, Empty) => true,
+            (
+                NotSquare { rows, cols },
+                NotSquare {
+                    rows: other_rows,
+                    cols: other_cols,
+                },
+            ) => rows == other_rows && cols == other_cols,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ReadError {}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Csv { source, .. } => Some(&**source),
+            ReadError::Io(io_error) => Some(&**io_error),
+            _ => None,
+        }
+    }
+}
+
+/// A problem with CSV data that didn't stop it from being read, from
+/// `Array2Reader::deserialize_array2_dynamic_checked`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    /// The row at `at_row_index` ended with an empty field, which was dropped
+    TrailingEmptyField { at_row_index: usize },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Warning::TrailingEmptyField { at_row_index } => write!(
+                f,
+                "Row {} had a trailing empty field that was dropped",
+                at_row_index
             ),
         }
     }
 }
 
-impl Error for ReadError {}
+/// A summary of the shape of CSV data, from `Array2Reader::deserialize_array2_dynamic_report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ShapeReport {
+    pub n_rows: usize,
+    pub n_columns: usize,
+    /// The fewest fields in any row
+    pub min_fields: usize,
+    /// The most fields in any row
+    pub max_fields: usize,
+    /// The number of rows whose last field is empty. This usually means that the row ends with a
+    /// delimiter, which adds an empty column that may not be intended.
+    pub rows_with_trailing_empty_field: usize,
+}
 
-impl<'a, R: Read> Array2Reader for &'a mut Reader<R> {
+impl<R: Read> Array2Reader for &mut Reader<R> {
     fn deserialize_array2<A: DeserializeOwned>(
         self,
         shape: (usize, usize),
     ) -> Result<Array2<A>, ReadError> {
-        let (n_rows, n_columns) = shape;
-
-        let rows = self.deserialize::<Vec<A>>();
-        let values = rows.enumerate().flat_map(|(row_index, row)| match row {
-            Err(e) => Either::Left(once(Err(ReadError::Csv(e)))),
-            Ok(row_vec) => Either::Right(if row_vec.len() == n_columns {
-                Either::Right(row_vec.into_iter().map(Ok))
-            } else {
-                Either::Left(once(Err(ReadError::NColumns {
-                    at_row_index: row_index,
-                    expected: n_columns,
-                    actual: row_vec.len(),
-                })))
-            }),
-        });
-        let array1_result: Result<Array1<A>, _> = values.collect();
-        array1_result.and_then(|array1| {
-            let array1_len = array1.len();
-            #[allow(deprecated)]
-            array1.into_shape(shape).map_err(|_| ReadError::NRows {
-                expected: n_rows,
-                actual: array1_len / n_columns,
-            })
-        })
+        deserialize_shape(RecordReader::new(self), shape, |_| ())
     }
 
     fn deserialize_array2_dynamic<A: DeserializeOwned>(self) -> Result<Array2<A>, ReadError> {
-        let mut row_count = 0;
-        let mut last_columns = None;
-
-        let rows = self.deserialize::<Vec<A>>();
-        let values = rows.enumerate().flat_map(|(row_index, row)| {
-            row_count += 1;
-            match row {
-                Err(e) => Either::Left(once(Err(ReadError::Csv(e)))),
-                Ok(row_vec) => {
-                    if let Some(last_columns) = last_columns {
-                        if last_columns != row_vec.len() {
-                            return Either::Right(Either::Left(once(Err(ReadError::NColumns {
-                                at_row_index: row_index,
-                                expected: last_columns,
-                                actual: row_vec.len(),
-                            }))));
-                        }
-                    };
-                    last_columns = Some(row_vec.len());
-                    Either::Right(Either::Right(row_vec.into_iter().map(Ok)))
-                }
-            }
-        });
-        let array1_result: Result<Array1<A>, _> = values.collect();
-        array1_result.map(|array1| {
-            #[allow(deprecated)]
-            array1
-                .into_shape((row_count, last_columns.unwrap_or(0)))
-                .unwrap()
-        })
+        let mut rows = DynamicRows::new();
+        rows.read(&mut RecordReader::new(self), None, None)?;
+        rows.into_nonempty_array()
     }
-}
 
-/// An extension trait; this is implemented by `&mut csv::Writer`
-pub trait Array2Writer {
-    /// Write this ndarray into CSV format
-    fn serialize_array2<A: Serialize>(self, array: &Array2<A>) -> Result<(), csv::Error>;
-}
-
-impl<'a, W: Write> Array2Writer for &'a mut Writer<W> {
-    fn serialize_array2<A: Serialize>(self, array: &Array2<A>) -> Result<(), csv::Error> {
-        /// This wraps the iterator for a row so that we can implement Serialize.
-        ///
-        /// Serialize is not implemented for iterators: https://github.com/serde-rs/serde/issues/571
-        ///
-        /// This solution from Hyeonu wraps the iterator:
-        /// https://users.rust-lang.org/t/how-to-serialize-an-iterator-to-json/59272/3
-        struct Row1DIter<'b, B>(Cell<Option<Iter<'b, B, Dim<[usize; 1]>>>>);
-
-        impl<'b, B> Serialize for Row1DIter<'b, B>
-        where
-            B: Serialize,
-        {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: Serializer,
-            {
-                serializer.collect_seq(self.0.take().unwrap())
+    fn deserialize_array2_total<A: DeserializeOwned>(
+        self,
+        total_elements: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        if total_elements == 0 {
+            return Ok(Array2::from_shape_vec((0, 0), Vec::new()).unwrap());
+        }
+        let mut records = RecordReader::new(self);
+        let mut rows = DynamicRows::new();
+        while records.read()? {
+            rows.push(&records)?;
+            let n_columns = rows.n_columns.unwrap();
+            if rows.n_rows == 1 && (n_columns == 0 || total_elements % n_columns != 0) {
+                return Err(ReadError::TotalElements {
+                    total_elements,
+                    n_columns,
+                });
+            }
+            if rows.values.len() == total_elements {
+                return Ok(rows.into_array());
             }
         }
+        match rows.n_columns {
+            Some(n_columns) => Err(ReadError::NRows {
+                expected: total_elements / n_columns,
+                actual: rows.n_rows,
+            }),
+            None => Err(ReadError::Empty),
+        }
+    }
 
-        for row in array.outer_iter() {
-            self.serialize(Row1DIter(Cell::new(Some(row.iter()))))?;
+    fn deserialize_array2_dynamic_checked<A: DeserializeOwned>(
+        self,
+    ) -> Result<(Array2<A>, Vec<Warning>), ReadError> {
+        let mut records = RecordReader::new(self);
+        let mut rows = DynamicRows::new();
+        let mut warnings = Vec::new();
+        while records.read()? {
+            let n_fields = records.record.len();
+            if n_fields > 1 && records.record.get(n_fields - 1) == Some("") {
+                records.record.truncate(n_fields - 1);
+                warnings.push(Warning::TrailingEmptyField {
+                    at_row_index: records.row_index(),
+                });
+            }
+            rows.push(&records)?;
         }
-        self.flush()?;
-        Ok(())
+        Ok((rows.into_nonempty_array()?, warnings))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::ReadError::*;
-    use super::*;
-    use csv::{Reader, ReaderBuilder, WriterBuilder};
-    use std::io::Cursor;
+    fn deserialize_array2_dynamic_with_order<A: DeserializeOwned>(
+        self,
+        order: Order,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut rows = DynamicRows::new();
+        rows.read(&mut RecordReader::new(self), None, None)?;
+        if rows.n_rows == 0 {
+            return Err(ReadError::Empty);
+        }
+        if order.is_column_major() {
+            Ok(rows.into_f_order_array())
+        } else {
+            Ok(rows.into_array())
+        }
+    }
 
-    fn in_memory_reader(content: &'static str) -> Reader<impl Read> {
-        ReaderBuilder::new()
-            .has_headers(false)
-            .from_reader(Cursor::new(content))
+    fn deserialize_array2_dynamic_or_empty<A: DeserializeOwned>(
+        self,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut rows = DynamicRows::new();
+        rows.read(&mut RecordReader::new(self), None, None)?;
+        Ok(rows.into_array())
     }
 
-    fn test_reader() -> Reader<impl Read> {
-        in_memory_reader("1,2,3\n4,5,6\n")
+    fn deserialize_array2_dynamic_report<A: DeserializeOwned>(
+        self,
+    ) -> Result<(Array2<A>, ShapeReport), ReadError> {
+        let mut rows = DynamicRows::new();
+        rows.read(&mut RecordReader::new(self), None, None)?;
+        let report = rows.report;
+        Ok((rows.into_nonempty_array()?, report))
     }
 
-    #[test]
-    fn test_read_float() {
-        let actual: Array2<f64> = test_reader().deserialize_array2((2, 3)).unwrap();
-        let expected = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
-        assert_eq!(actual, expected);
+    fn deserialize_array2_rows_dynamic<A: DeserializeOwned>(
+        self,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut records = RecordReader::new(self);
+        let mut values = Vec::new();
+
+        while records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            records.check_n_columns(n_columns, row_vec.len())?;
+            values.extend(row_vec);
+        }
+
+        Ok(Array2::from_shape_vec((records.row_count(), n_columns), values).unwrap())
     }
 
-    #[test]
-    fn test_read_integer() {
-        let actual: Array2<u64> = test_reader().deserialize_array2((2, 3)).unwrap();
-        let expected = array![[1, 2, 3], [4, 5, 6]];
-        assert_eq!(actual, expected);
+    fn deserialize_array2_square<A: DeserializeOwned>(self) -> Result<Array2<A>, ReadError> {
+        let array = self.deserialize_array2_dynamic()?;
+        let (rows, cols) = array.dim();
+        if rows == cols {
+            Ok(array)
+        } else {
+            Err(ReadError::NotSquare { rows, cols })
+        }
     }
 
-    #[test]
+    fn deserialize_array2_dynamic_capped<A: DeserializeOwned>(
+        self,
+        max_rows: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut rows = DynamicRows::new();
+        rows.read(&mut RecordReader::new(self), Some(max_rows), None)?;
+        rows.into_nonempty_array()
+    }
+
+    fn deserialize_array2_dynamic_max_cols<A: DeserializeOwned>(
+        self,
+        max_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut rows = DynamicRows::new();
+        rows.read(&mut RecordReader::new(self), None, Some(max_columns))?;
+        rows.into_nonempty_array()
+    }
+
+    fn deserialize_array2_dynamic_partial<A: DeserializeOwned>(
+        self,
+    ) -> (Array2<A>, Option<ReadError>) {
+        let mut rows = DynamicRows::new();
+        let error = rows.read(&mut RecordReader::new(self), None, None).err();
+        (rows.into_array(), error)
+    }
+
+    fn deserialize_array2_with_shape<A: DeserializeOwned>(
+        self,
+    ) -> Result<(Array2<A>, (usize, usize)), ReadError> {
+        let array = self.deserialize_array2_dynamic()?;
+        let shape = array.dim();
+        Ok((array, shape))
+    }
+
+    fn deserialize_array2_padded<A: DeserializeOwned + Default>(
+        self,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut records = RecordReader::new(self);
+        let mut values = Vec::new();
+
+        while records.read()? {
+            let mut row_vec: Vec<A> = records.deserialize()?;
+            if row_vec.len() > n_columns {
+                return Err(records.n_columns_error(n_columns, row_vec.len()));
+            }
+            row_vec.resize_with(n_columns, A::default);
+            values.extend(row_vec);
+        }
+
+        Ok(Array2::from_shape_vec((records.row_count(), n_columns), values).unwrap())
+    }
+
+    fn deserialize_array2_with_headers<A: DeserializeOwned>(
+        self,
+        n_columns: usize,
+    ) -> Result<(Vec<String>, Array2<A>), ReadError> {
+        let csv_error = |e| ReadError::Csv {
+            source: Arc::new(e),
+            at_row_index: 0,
+        };
+        let headers = if self.has_headers() {
+            self.headers().map_err(csv_error)?.clone()
+        } else {
+            let mut record = StringRecord::new();
+            self.read_record(&mut record).map_err(csv_error)?;
+            record
+        };
+        if headers.len() != n_columns {
+            return Err(ReadError::NHeaders {
+                expected: n_columns,
+                actual: headers.len(),
+            });
+        }
+        let mut names = HashSet::new();
+        if let Some(name) = headers.iter().find(|&name| !names.insert(name)) {
+            return Err(ReadError::DuplicateHeader {
+                name: name.to_string(),
+            });
+        }
+
+        let array = self.deserialize_array2_rows_dynamic(n_columns)?;
+        Ok((headers.iter().map(String::from).collect(), array))
+    }
+
+    fn deserialize_array2_columns<A: DeserializeOwned>(
+        self,
+        columns: &[usize],
+        n_rows: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut records = RecordReader::new(self);
+        let mut selected = StringRecord::new();
+        let mut values = Vec::with_capacity(reserved_capacity(n_rows, columns.len()));
+
+        while records.read()? {
+            selected.clear();
+            for &column in columns {
+                match records.record.get(column) {
+                    Some(field) => selected.push_field(field),
+                    None => {
+                        return Err(ReadError::NoSuchColumn {
+                            at_row_index: records.row_index(),
+                            column,
+                            n_columns: records.record.len(),
+                        })
+                    }
+                }
+            }
+            // Report the column's index in the CSV data, not in the selection
+            let row_vec: Vec<A> = deserialize_record(&selected, records.row_index())
+                .map_err(|e| map_col_index(e, |i| columns[i]))?;
+            values.extend(row_vec);
+        }
+
+        if records.row_count() == n_rows {
+            Ok(Array2::from_shape_vec((n_rows, columns.len()), values).unwrap())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    fn deserialize_array2_skip<A: DeserializeOwned>(
+        self,
+        skip_rows: usize,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        let mut record = ByteRecord::new();
+        for row_index in 0..skip_rows {
+            let more = self
+                .read_byte_record(&mut record)
+                .map_err(|e| ReadError::Csv {
+                    source: Arc::new(e),
+                    at_row_index: row_index,
+                })?;
+            if !more {
+                break;
+            }
+        }
+        self.deserialize_array2(shape)
+    }
+
+    fn deserialize_array2_head<A: DeserializeOwned>(
+        self,
+        k: usize,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut records = RecordReader::new(self);
+        // k may be far more than the number of rows, e.g. usize::MAX to read everything
+        let mut values = Vec::with_capacity(reserved_capacity(k, n_columns));
+        while records.row_count() < k && records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            records.check_n_columns(n_columns, row_vec.len())?;
+            values.extend(row_vec);
+        }
+        Ok(Array2::from_shape_vec((records.row_count(), n_columns), values).unwrap())
+    }
+
+    fn deserialize_array2_tail<A: DeserializeOwned>(
+        self,
+        k: usize,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut records = RecordReader::new(self);
+        // A ring buffer of the most recent rows
+        let mut rows = VecDeque::with_capacity(k.min(1024));
+        while records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            records.check_n_columns(n_columns, row_vec.len())?;
+            if k == 0 {
+                continue;
+            }
+            if rows.len() == k {
+                rows.pop_front();
+            }
+            rows.push_back(row_vec);
+        }
+        let n_rows = rows.len();
+        let values = rows.into_iter().flatten().collect();
+        Ok(Array2::from_shape_vec((n_rows, n_columns), values).unwrap())
+    }
+
+    fn deserialize_array2_with_shape_header<A: DeserializeOwned>(
+        self,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut header = StringRecord::new();
+        let more = self.read_record(&mut header).map_err(|e| ReadError::Csv {
+            source: Arc::new(e),
+            at_row_index: 0,
+        })?;
+        if !more {
+            return Err(ReadError::Empty);
+        }
+        let shape = parse_shape_header(&header)?;
+        self.deserialize_array2(shape)
+    }
+
+    fn deserialize_array2_trimmed<A: DeserializeOwned>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        let mut records = RecordReader::new(self);
+        records.trim = true;
+        deserialize_shape(records, shape, |_| ())
+    }
+
+    fn deserialize_array2_map_cells<A: DeserializeOwned, F: FnMut(&str) -> Cow<str>>(
+        self,
+        shape: (usize, usize),
+        mut f: F,
+    ) -> Result<Array2<A>, ReadError> {
+        let (n_rows, n_columns) = shape;
+        let mut records = RecordReader::new(self);
+        let mut mapped = StringRecord::new();
+        let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+
+        while records.read()? {
+            records.check_n_columns(n_columns, records.record.len())?;
+            mapped.clear();
+            for field in records.record.iter() {
+                mapped.push_field(&f(field));
+            }
+            let row_vec: Vec<A> = deserialize_record(&mapped, records.row_index())?;
+            values.extend(row_vec);
+        }
+
+        if records.row_count() == n_rows {
+            Ok(Array2::from_shape_vec(shape, values).unwrap())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    fn deserialize_array2_with_progress<A: DeserializeOwned, F: FnMut(usize)>(
+        self,
+        shape: (usize, usize),
+        every: usize,
+        mut on_progress: F,
+    ) -> Result<Array2<A>, ReadError> {
+        let mut last_reported = None;
+        let array = deserialize_shape(RecordReader::new(self), shape, |rows_done| {
+            if every != 0 && rows_done % every == 0 {
+                on_progress(rows_done);
+                last_reported = Some(rows_done);
+            }
+        })?;
+        if last_reported != Some(array.nrows()) {
+            on_progress(array.nrows());
+        }
+        Ok(array)
+    }
+
+    fn deserialize_array2_bool(
+        self,
+        shape: (usize, usize),
+        truthy: &[&str],
+        falsy: &[&str],
+    ) -> Result<Array2<bool>, ReadError> {
+        parse_fields(RecordReader::new(self), shape, |field| {
+            if truthy.contains(&field) {
+                Some(true)
+            } else if falsy.contains(&field) {
+                Some(false)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn deserialize_array2_chars(self, shape: (usize, usize)) -> Result<Array2<char>, ReadError> {
+        parse_fields(RecordReader::new(self), shape, |field| {
+            let mut chars = field.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            }
+        })
+    }
+
+    fn deserialize_array2_with_sentinel<A: DeserializeOwned + Clone>(
+        self,
+        shape: (usize, usize),
+        sentinel: &str,
+        replacement: A,
+    ) -> Result<Array2<A>, ReadError> {
+        let (n_rows, n_columns) = shape;
+        let mut records = RecordReader::new(self);
+        let mut field_record = StringRecord::new();
+        let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+
+        while records.read()? {
+            records.check_n_columns(n_columns, records.record.len())?;
+            for (col_index, field) in records.record.iter().enumerate() {
+                if field == sentinel {
+                    values.push(replacement.clone());
+                } else {
+                    field_record.clear();
+                    field_record.push_field(field);
+                    let value = deserialize_record(&field_record, records.row_index())
+                        .map_err(|e| map_col_index(e, |_| col_index))?;
+                    values.push(value);
+                }
+            }
+        }
+
+        if records.row_count() == n_rows {
+            Ok(Array2::from_shape_vec(shape, values).unwrap())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    fn deserialize_array2_lossy<A: DeserializeOwned + Clone>(
+        self,
+        shape: (usize, usize),
+        fill: A,
+    ) -> Result<(Array2<A>, Vec<CellIndex>), ReadError> {
+        let (n_rows, n_columns) = shape;
+        let mut records = RecordReader::new(self);
+        let mut field_record = StringRecord::new();
+        let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+        let mut filled = Vec::new();
+
+        while records.read()? {
+            records.check_n_columns(n_columns, records.record.len())?;
+            for (col_index, field) in records.record.iter().enumerate() {
+                field_record.clear();
+                field_record.push_field(field);
+                match deserialize_record(&field_record, records.row_index()) {
+                    Ok(value) => values.push(value),
+                    Err(ReadError::Parse { .. }) | Err(ReadError::OutOfRange { .. }) => {
+                        values.push(fill.clone());
+                        filled.push((records.row_index(), col_index));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        if records.row_count() == n_rows {
+            Ok((Array2::from_shape_vec(shape, values).unwrap(), filled))
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    fn deserialize_array2_with_index<I: DeserializeOwned, A: DeserializeOwned>(
+        self,
+        n_columns: usize,
+    ) -> Result<(Vec<I>, Array2<A>), ReadError> {
+        let mut records = RecordReader::new(self);
+        let mut index = Vec::new();
+        let mut values = Vec::new();
+
+        while records.read()? {
+            let (label, row_vec): (I, Vec<A>) = records.deserialize()?;
+            records.check_n_columns(n_columns, row_vec.len())?;
+            index.push(label);
+            values.extend(row_vec);
+        }
+
+        let array = Array2::from_shape_vec((index.len(), n_columns), values).unwrap();
+        Ok((index, array))
+    }
+
+    fn deserialize_array2_split<K: DeserializeOwned, A: DeserializeOwned>(
+        self,
+        key_columns: usize,
+        n_value_columns: usize,
+    ) -> Result<(Vec<K>, Array2<A>), ReadError> {
+        let mut records = RecordReader::new(self);
+        let mut key_record = StringRecord::new();
+        let mut value_record = StringRecord::new();
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+
+        while records.read()? {
+            key_record.clear();
+            value_record.clear();
+            for (col_index, field) in records.record.iter().enumerate() {
+                if col_index < key_columns {
+                    key_record.push_field(field);
+                } else {
+                    value_record.push_field(field);
+                }
+            }
+            let key: K = deserialize_record(&key_record, records.row_index())?;
+            let row_vec: Vec<A> = deserialize_record(&value_record, records.row_index())
+                .map_err(|e| map_col_index(e, |i| i + key_columns))?;
+            records.check_n_columns(n_value_columns, row_vec.len())?;
+            keys.push(key);
+            values.extend(row_vec);
+        }
+
+        let array = Array2::from_shape_vec((keys.len(), n_value_columns), values).unwrap();
+        Ok((keys, array))
+    }
+
+    fn deserialize_ragged<A: DeserializeOwned>(self) -> Result<Vec<Array1<A>>, ReadError> {
+        let mut records = RecordReader::new(self);
+        let mut rows = Vec::new();
+        while records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            rows.push(Array1::from(row_vec));
+        }
+        Ok(rows)
+    }
+
+    fn deserialize_array2_transposed<A: DeserializeOwned>(
+        self,
+        logical_shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        let (n_rows, n_columns) = logical_shape;
+        self.deserialize_array2((n_columns, n_rows))
+            .map(Array2::reversed_axes)
+    }
+
+    fn deserialize_array2_f_order<A: DeserializeOwned>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        let (n_rows, n_columns) = shape;
+        let mut records = RecordReader::new(self);
+        let mut rows = Vec::with_capacity(reserved_capacity(n_rows, 1));
+
+        while records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            records.check_n_columns(n_columns, row_vec.len())?;
+            rows.push(row_vec.into_iter());
+        }
+        if rows.len() != n_rows {
+            return Err(ReadError::NRows {
+                expected: n_rows,
+                actual: rows.len(),
+            });
+        }
+
+        // Take one value from each row in turn to build up the columns. The shape has been checked
+        // against the rows that were read, so it's safe to reserve exactly.
+        let mut values = Vec::with_capacity(n_rows * n_columns);
+        for _ in 0..n_columns {
+            for row in &mut rows {
+                values.push(row.next().unwrap());
+            }
+        }
+        Ok(Array2::from_shape_vec(shape.f(), values).unwrap())
+    }
+
+    fn deserialize_columns<A: DeserializeOwned>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Vec<Array1<A>>, ReadError> {
+        let (n_rows, n_columns) = shape;
+        let mut records = RecordReader::new(self);
+        let mut columns: Vec<Vec<A>> = Vec::new();
+
+        while records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            records.check_n_columns(n_columns, row_vec.len())?;
+            if columns.is_empty() {
+                // The first row has confirmed the number of columns
+                let per_column = reserved_capacity(n_rows, n_columns) / n_columns.max(1);
+                columns.resize_with(n_columns, || Vec::with_capacity(per_column));
+            }
+            for (column, value) in columns.iter_mut().zip(row_vec) {
+                column.push(value);
+            }
+        }
+
+        if records.row_count() == n_rows {
+            // Without any rows, no columns were made yet
+            columns.resize_with(n_columns, Vec::new);
+            Ok(columns.into_iter().map(Array1::from).collect())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    fn deserialize_array2_fast<A: FromStr>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        parse_byte_fields(self, shape, |field| {
+            str::from_utf8(field)
+                .ok()
+                .and_then(|field| field.parse().ok())
+                .ok_or(FieldError::Invalid)
+        })
+    }
+
+    fn deserialize_array2_u8(self, shape: (usize, usize)) -> Result<Array2<u8>, ReadError> {
+        parse_byte_fields(self, shape, parse_u8)
+    }
+
+    fn deserialize_array2_coerce<A: FromStr>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        parse_byte_fields(self, shape, |field| {
+            let field = str::from_utf8(field).map_err(|_| FieldError::Invalid)?;
+            field
+                .parse()
+                .ok()
+                .or_else(|| whole_number(field)?.parse().ok())
+                .ok_or(FieldError::Invalid)
+        })
+    }
+
+    #[cfg(feature = "num-complex")]
+    fn deserialize_array2_complex<A: DeserializeOwned>(
+        self,
+        shape_in_complex: (usize, usize),
+    ) -> Result<Array2<Complex<A>>, ReadError> {
+        let (n_rows, n_columns) = shape_in_complex;
+        let mut records = RecordReader::new(self);
+        let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+
+        while records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            records.check_n_columns(2 * n_columns, row_vec.len())?;
+            let mut fields = row_vec.into_iter();
+            while let (Some(re), Some(im)) = (fields.next(), fields.next()) {
+                values.push(Complex::new(re, im));
+            }
+        }
+
+        if records.row_count() == n_rows {
+            Ok(Array2::from_shape_vec(shape_in_complex, values).unwrap())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn deserialize_array2_par<A: DeserializeOwned + Send>(
+        self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        let (n_rows, n_columns) = shape;
+        let mut records = Vec::with_capacity(reserved_capacity(n_rows, 1));
+
+        for (row_index, record) in self.records().enumerate() {
+            let record = record.map_err(|e| ReadError::Csv {
+                source: Arc::new(e),
+                at_row_index: row_index,
+            })?;
+            if record.len() != n_columns {
+                return Err(ReadError::NColumns {
+                    at_row_index: row_index,
+                    expected: n_columns,
+                    actual: record.len(),
+                    line: record.position().map(Position::line),
+                });
+            }
+            records.push(record);
+        }
+        if records.len() != n_rows {
+            return Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.len(),
+            });
+        }
+
+        // Collect every row's result before looking at any of them, so that the error that's
+        // returned doesn't depend on how the work was scheduled
+        let rows: Vec<Result<Vec<A>, ReadError>> = records
+            .par_iter()
+            .enumerate()
+            .map(|(row_index, record)| deserialize_record(record, row_index))
+            .collect();
+        // The shape has been checked against the records that were read
+        let mut values = Vec::with_capacity(n_rows * n_columns);
+        for row in rows {
+            values.extend(row?);
+        }
+        Ok(Array2::from_shape_vec(shape, values).unwrap())
+    }
+
+    fn deserialize_array2_into<A: DeserializeOwned>(
+        self,
+        out: &mut Array2<A>,
+    ) -> Result<(), ReadError> {
+        let (n_rows, n_columns) = out.dim();
+        let mut records = RecordReader::new(self);
+
+        while records.read()? {
+            let row_vec: Vec<A> = records.deserialize()?;
+            records.check_n_columns(n_columns, row_vec.len())?;
+            // Keep reading past the end of the buffer so that the error reports the actual number
+            // of rows, like deserialize_array2 does
+            if records.row_index() < n_rows {
+                for (cell, value) in out.row_mut(records.row_index()).iter_mut().zip(row_vec) {
+                    *cell = value;
+                }
+            }
+        }
+
+        if records.row_count() == n_rows {
+            Ok(())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    fn validate_shape(self, expected: (usize, usize)) -> Result<(), ReadError> {
+        let (n_rows, n_columns) = expected;
+        let mut records = RecordReader::new(self);
+
+        while records.read()? {
+            records.check_n_columns(n_columns, records.record.len())?;
+        }
+
+        if records.row_count() == n_rows {
+            Ok(())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+
+    fn analyze_columns(self, max_rows: usize) -> Result<ColumnHistogram, ReadError> {
+        let mut histogram = ColumnHistogram::default();
+        let mut record = ByteRecord::new();
+        loop {
+            let more = self
+                .read_byte_record(&mut record)
+                .map_err(|e| ReadError::Csv {
+                    source: Arc::new(e),
+                    at_row_index: histogram.n_rows,
+                })?;
+            if !more {
+                break;
+            }
+            if histogram.n_rows == max_rows {
+                histogram.truncated = true;
+                break;
+            }
+            if record.len() > histogram.max_columns() || histogram.widest_row.is_none() {
+                histogram.widest_row = Some(histogram.n_rows);
+            }
+            *histogram.counts.entry(record.len()).or_insert(0) += 1;
+            histogram.n_rows += 1;
+        }
+        Ok(histogram)
+    }
+}
+
+/// Why `parse_byte_fields` couldn't parse a field
+enum FieldError {
+    /// The field isn't a value of the type at all
+    Invalid,
+    /// The field is a number, but it's too large or too small for the type
+    OutOfRange,
+}
+
+/// Read byte records directly from the reader into a new ndarray with the given shape, using
+/// `parse` to turn each field into a value
+///
+/// This avoids both serde and the `RecordReader`'s `StringRecord`, which needs valid UTF-8.
+fn parse_byte_fields<R: Read, T, F: Fn(&[u8]) -> Result<T, FieldError>>(
+    reader: &mut Reader<R>,
+    shape: (usize, usize),
+    parse: F,
+) -> Result<Array2<T>, ReadError> {
+    let (n_rows, n_columns) = shape;
+    let mut record = ByteRecord::new();
+    let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+    let mut row_count = 0;
+
+    loop {
+        let more = reader
+            .read_byte_record(&mut record)
+            .map_err(|e| ReadError::Csv {
+                source: Arc::new(e),
+                at_row_index: row_count,
+            })?;
+        if !more {
+            break;
+        }
+        if record.len() != n_columns {
+            return Err(ReadError::NColumns {
+                at_row_index: row_count,
+                expected: n_columns,
+                actual: record.len(),
+                line: record.position().map(Position::line),
+            });
+        }
+        for (col_index, field) in record.iter().enumerate() {
+            let value = parse(field).map_err(|error| {
+                let value = String::from_utf8_lossy(field).into_owned();
+                match error {
+                    FieldError::Invalid => ReadError::Parse {
+                        at_row_index: row_count,
+                        at_col_index: col_index,
+                        value,
+                    },
+                    FieldError::OutOfRange => ReadError::OutOfRange {
+                        at_row_index: row_count,
+                        at_col_index: col_index,
+                        value,
+                    },
+                }
+            })?;
+            values.push(value);
+        }
+        row_count += 1;
+    }
+
+    if row_count == n_rows {
+        Ok(Array2::from_shape_vec(shape, values).unwrap())
+    } else {
+        Err(ReadError::NRows {
+            expected: n_rows,
+            actual: row_count,
+        })
+    }
+}
+
+/// The integer part of `field` if it's a decimal with nothing but zeros after the point, e.g.
+/// `"42"` for `"42.00"`
+fn whole_number(field: &str) -> Option<&str> {
+    let (integer, fraction) = field.split_at(field.find('.')?);
+    if fraction[1..].bytes().all(|b| b == b'0') {
+        Some(integer)
+    } else {
+        None
+    }
+}
+
+/// Parse a field of ASCII decimal digits as a `u8`
+fn parse_u8(field: &[u8]) -> Result<u8, FieldError> {
+    if field.is_empty() || !field.iter().all(u8::is_ascii_digit) {
+        return Err(FieldError::Invalid);
+    }
+    let mut value: u16 = 0;
+    for &digit in field {
+        value = value * 10 + u16::from(digit - b'0');
+        if value > u16::from(u8::MAX) {
+            return Err(FieldError::OutOfRange);
+        }
+    }
+    Ok(value as u8)
+}
+
+/// The most elements that are reserved up front for an array whose shape hasn't been checked
+/// against the data yet
+const MAX_RESERVED_ELEMENTS: usize = 1 << 20;
+
+/// How many elements to reserve for an array with the given shape before reading it
+///
+/// The shape comes from the caller or from the data, and it's only checked once the data has been
+/// read, so a wrong shape mustn't overflow or make the allocation fail. Past the cap, the `Vec`
+/// grows as usual.
+fn reserved_capacity(n_rows: usize, n_columns: usize) -> usize {
+    n_rows
+        .checked_mul(n_columns)
+        .map_or(MAX_RESERVED_ELEMENTS, |len| len.min(MAX_RESERVED_ELEMENTS))
+}
+
+/// Read the records into a new ndarray with the given shape, calling `on_row` with the number of
+/// rows read so far after each row
+fn deserialize_shape<R: Read, A: DeserializeOwned, F: FnMut(usize)>(
+    mut records: RecordReader<R>,
+    shape: (usize, usize),
+    mut on_row: F,
+) -> Result<Array2<A>, ReadError> {
+    let (n_rows, n_columns) = shape;
+
+    // Write straight into the array's backing Vec, which needs no reallocation if the shape is
+    // right and not too large
+    let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+    while records.read()? {
+        let row_vec: Vec<A> = records.deserialize()?;
+        records.check_n_columns(n_columns, row_vec.len())?;
+        values.extend(row_vec);
+        on_row(records.row_count());
+    }
+
+    // Every row was checked to have exactly n_columns values, so the shape is right if and only
+    // if the number of rows is. Comparing the rows directly also covers n_columns == 0, where the
+    // number of values says nothing about the number of rows.
+    if records.row_count() == n_rows {
+        Ok(Array2::from_shape_vec(shape, values).unwrap())
+    } else {
+        Err(ReadError::NRows {
+            expected: n_rows,
+            actual: records.row_count(),
+        })
+    }
+}
+
+/// Read the records into a new ndarray with the given shape, using `parse` to turn each field
+/// into a value. Fields for which `parse` returns `None` are `Parse` errors.
+fn parse_fields<R: Read, T, F: Fn(&str) -> Option<T>>(
+    mut records: RecordReader<R>,
+    shape: (usize, usize),
+    parse: F,
+) -> Result<Array2<T>, ReadError> {
+    let (n_rows, n_columns) = shape;
+    let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+
+    while records.read()? {
+        records.check_n_columns(n_columns, records.record.len())?;
+        for (col_index, field) in records.record.iter().enumerate() {
+            let value = parse(field).ok_or_else(|| ReadError::Parse {
+                at_row_index: records.row_index(),
+                at_col_index: col_index,
+                value: field.to_string(),
+            })?;
+            values.push(value);
+        }
+    }
+
+    if records.row_count() == n_rows {
+        Ok(Array2::from_shape_vec(shape, values).unwrap())
+    } else {
+        Err(ReadError::NRows {
+            expected: n_rows,
+            actual: records.row_count(),
+        })
+    }
+}
+
+/// Deserialize a record, reporting a field that fails to deserialize as a `Parse` error
+fn deserialize_record<D: DeserializeOwned>(
+    record: &StringRecord,
+    at_row_index: usize,
+) -> Result<D, ReadError> {
+    record.deserialize(None).map_err(|e| {
+        let (field, out_of_range) = match e.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => {
+                let out_of_range = match err.kind() {
+                    csv::DeserializeErrorKind::ParseInt(parse_error) => matches!(
+                        parse_error.kind(),
+                        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+                    ),
+                    _ => false,
+                };
+                (err.field(), out_of_range)
+            }
+            _ => (None, false),
+        };
+        match field.and_then(|field| Some((field as usize, record.get(field as usize)?))) {
+            Some((at_col_index, value)) if out_of_range => ReadError::OutOfRange {
+                at_row_index,
+                at_col_index,
+                value: value.to_string(),
+            },
+            Some((at_col_index, value)) => ReadError::Parse {
+                at_row_index,
+                at_col_index,
+                value: value.to_string(),
+            },
+            None => ReadError::Csv {
+                source: Arc::new(e),
+                at_row_index,
+            },
+        }
+    })
+}
+
+/// Parse a shape header record, which is either the number of rows and columns followed by any
+/// number of empty fields, or a single field such as `2x1`
+fn parse_shape_header(header: &StringRecord) -> Result<(usize, usize), ReadError> {
+    let parse_error = |at_col_index: usize, value: &str| ReadError::Parse {
+        at_row_index: 0,
+        at_col_index,
+        value: value.to_string(),
+    };
+    let shape: (usize, usize) = match header.len() {
+        0 => {
+            return Err(ReadError::NColumns {
+                at_row_index: 0,
+                expected: 2,
+                actual: 0,
+                line: header.position().map(Position::line),
+            })
+        }
+        1 => {
+            let value = &header[0];
+            value
+                .split_once('x')
+                .and_then(|(n_rows, n_columns)| {
+                    Some((n_rows.parse().ok()?, n_columns.parse().ok()?))
+                })
+                .ok_or_else(|| parse_error(0, value))?
+        }
+        _ => {
+            let size = |at_col_index: usize| {
+                let value = &header[at_col_index];
+                value.parse().map_err(|_| parse_error(at_col_index, value))
+            };
+            let shape = (size(0)?, size(1)?);
+            if let Some((at_col_index, value)) = header
+                .iter()
+                .enumerate()
+                .skip(2)
+                .find(|(_, value)| !value.is_empty())
+            {
+                return Err(parse_error(at_col_index, value));
+            }
+            shape
+        }
+    };
+    let (n_rows, n_columns) = shape;
+    if n_rows.checked_mul(n_columns).is_none() {
+        return Err(ReadError::ShapeOverflow { n_rows, n_columns });
+    }
+    Ok(shape)
+}
+
+/// Change the column index of a `Parse` or `OutOfRange` error, for records made from a subset of
+/// the fields
+fn map_col_index<F: Fn(usize) -> usize>(error: ReadError, f: F) -> ReadError {
+    match error {
+        ReadError::Parse {
+            at_row_index,
+            at_col_index,
+            value,
+        } => ReadError::Parse {
+            at_row_index,
+            at_col_index: f(at_col_index),
+            value,
+        },
+        ReadError::OutOfRange {
+            at_row_index,
+            at_col_index,
+            value,
+        } => ReadError::OutOfRange {
+            at_row_index,
+            at_col_index: f(at_col_index),
+            value,
+        },
+        e => e,
+    }
+}
+
+/// Reads CSV records one at a time, keeping the current record around so that errors can describe
+/// it
+struct RecordReader<'r, R: 'r> {
+    reader: &'r mut Reader<R>,
+    record: StringRecord,
+    /// The number of records read so far, including the current one
+    row_count: usize,
+    /// Whether to trim whitespace from the fields of each record
+    trim: bool,
+}
+
+impl<'r, R: Read> RecordReader<'r, R> {
+    fn new(reader: &'r mut Reader<R>) -> Self {
+        RecordReader {
+            reader,
+            record: StringRecord::new(),
+            row_count: 0,
+            trim: false,
+        }
+    }
+
+    /// Read the next record, returning `false` at the end of the data
+    fn read(&mut self) -> Result<bool, ReadError> {
+        let row_index = self.row_count;
+        let more = self
+            .reader
+            .read_record(&mut self.record)
+            .map_err(|e| ReadError::Csv {
+                source: Arc::new(e),
+                at_row_index: row_index,
+            })?;
+        if more {
+            self.row_count += 1;
+            if self.trim {
+                self.record.trim();
+            }
+        }
+        Ok(more)
+    }
+
+    fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// The index of the current record
+    fn row_index(&self) -> usize {
+        self.row_count - 1
+    }
+
+    /// Deserialize the current record
+    fn deserialize<D: DeserializeOwned>(&self) -> Result<D, ReadError> {
+        deserialize_record(&self.record, self.row_index())
+    }
+
+    /// Check that the current record, which has `actual` columns, has `expected` columns
+    fn check_n_columns(&self, expected: usize, actual: usize) -> Result<(), ReadError> {
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(self.n_columns_error(expected, actual))
+        }
+    }
+
+    fn n_columns_error(&self, expected: usize, actual: usize) -> ReadError {
+        ReadError::NColumns {
+            at_row_index: self.row_index(),
+            expected,
+            actual,
+            line: self.record.position().map(Position::line),
+        }
+    }
+}
+
+/// The rows read so far by one of the readers that infer the shape of the data
+struct DynamicRows<A> {
+    values: Vec<A>,
+    n_rows: usize,
+    n_columns: Option<usize>,
+    report: ShapeReport,
+}
+
+impl<A: DeserializeOwned> DynamicRows<A> {
+    fn new() -> Self {
+        DynamicRows {
+            values: Vec::new(),
+            n_rows: 0,
+            n_columns: None,
+            report: ShapeReport::default(),
+        }
+    }
+
+    /// Read rows until the end of the data, checking that every row has the same number of columns
+    /// as the first one. Only complete rows are kept if there is an error.
+    fn read<R: Read>(
+        &mut self,
+        records: &mut RecordReader<R>,
+        max_rows: Option<usize>,
+        max_columns: Option<usize>,
+    ) -> Result<(), ReadError> {
+        while records.read()? {
+            if let Some(max) = max_rows {
+                if self.n_rows == max {
+                    return Err(ReadError::TooManyRows { max });
+                }
+            }
+            if let Some(max) = max_columns {
+                if records.record.len() > max {
+                    return Err(ReadError::TooManyColumns {
+                        at_row_index: records.row_index(),
+                        max,
+                    });
+                }
+            }
+            self.push(records)?;
+        }
+        self.report.n_rows = self.n_rows;
+        self.report.n_columns = self.n_columns.unwrap_or(0);
+        Ok(())
+    }
+
+    /// Add the current record as a row, checking that it has the same number of columns as the
+    /// first one
+    fn push<R: Read>(&mut self, records: &RecordReader<R>) -> Result<(), ReadError> {
+        let row_vec: Vec<A> = records.deserialize()?;
+        records.check_n_columns(*self.n_columns.get_or_insert(row_vec.len()), row_vec.len())?;
+        self.values.extend(row_vec);
+        self.n_rows += 1;
+
+        let n_fields = records.record.len();
+        if self.n_rows == 1 || n_fields < self.report.min_fields {
+            self.report.min_fields = n_fields;
+        }
+        self.report.max_fields = self.report.max_fields.max(n_fields);
+        if records.record.iter().next_back() == Some("") {
+            self.report.rows_with_trailing_empty_field += 1;
+        }
+        Ok(())
+    }
+
+    fn into_array(self) -> Array2<A> {
+        Array2::from_shape_vec((self.n_rows, self.n_columns.unwrap_or(0)), self.values).unwrap()
+    }
+
+    /// Like `into_array`, but laid out in column-major (Fortran) order
+    fn into_f_order_array(self) -> Array2<A> {
+        let shape = (self.n_rows, self.n_columns.unwrap_or(0));
+        let (n_rows, n_columns) = shape;
+
+        // Move each value from its row-major index to its column-major one
+        let mut slots: Vec<Option<A>> = (0..self.values.len()).map(|_| None).collect();
+        for (index, value) in self.values.into_iter().enumerate() {
+            slots[(index % n_columns) * n_rows + index / n_columns] = Some(value);
+        }
+        let values = slots.into_iter().map(Option::unwrap).collect();
+        Array2::from_shape_vec(shape.f(), values).unwrap()
+    }
+
+    /// Like `into_array`, but with an `Empty` error if no rows were read
+    fn into_nonempty_array(self) -> Result<Array2<A>, ReadError> {
+        if self.n_rows == 0 {
+            Err(ReadError::Empty)
+        } else {
+            Ok(self.into_array())
+        }
+    }
+}
+
+/// This consumes the reader, for when it's only used once
+impl<R: Read> Array2Reader for Reader<R> {
+    fn deserialize_array2<A: DeserializeOwned>(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2(shape)
+    }
+
+    fn deserialize_array2_dynamic<A: DeserializeOwned>(mut self) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_dynamic()
+    }
+
+    fn deserialize_array2_dynamic_with_order<A: DeserializeOwned>(
+        mut self,
+        order: Order,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_dynamic_with_order(order)
+    }
+
+    fn deserialize_array2_total<A: DeserializeOwned>(
+        mut self,
+        total_elements: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_total(total_elements)
+    }
+
+    fn deserialize_array2_dynamic_checked<A: DeserializeOwned>(
+        mut self,
+    ) -> Result<(Array2<A>, Vec<Warning>), ReadError> {
+        (&mut self).deserialize_array2_dynamic_checked()
+    }
+
+    fn deserialize_array2_dynamic_or_empty<A: DeserializeOwned>(
+        mut self,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_dynamic_or_empty()
+    }
+
+    fn deserialize_array2_dynamic_report<A: DeserializeOwned>(
+        mut self,
+    ) -> Result<(Array2<A>, ShapeReport), ReadError> {
+        (&mut self).deserialize_array2_dynamic_report()
+    }
+
+    fn deserialize_array2_rows_dynamic<A: DeserializeOwned>(
+        mut self,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_rows_dynamic(n_columns)
+    }
+
+    fn deserialize_array2_square<A: DeserializeOwned>(mut self) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_square()
+    }
+
+    fn deserialize_array2_dynamic_capped<A: DeserializeOwned>(
+        mut self,
+        max_rows: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_dynamic_capped(max_rows)
+    }
+
+    fn deserialize_array2_dynamic_max_cols<A: DeserializeOwned>(
+        mut self,
+        max_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_dynamic_max_cols(max_columns)
+    }
+
+    fn deserialize_array2_dynamic_partial<A: DeserializeOwned>(
+        mut self,
+    ) -> (Array2<A>, Option<ReadError>) {
+        (&mut self).deserialize_array2_dynamic_partial()
+    }
+
+    fn deserialize_array2_with_shape<A: DeserializeOwned>(
+        mut self,
+    ) -> Result<(Array2<A>, (usize, usize)), ReadError> {
+        (&mut self).deserialize_array2_with_shape()
+    }
+
+    fn deserialize_array2_padded<A: DeserializeOwned + Default>(
+        mut self,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_padded(n_columns)
+    }
+
+    fn deserialize_array2_with_headers<A: DeserializeOwned>(
+        mut self,
+        n_columns: usize,
+    ) -> Result<(Vec<String>, Array2<A>), ReadError> {
+        (&mut self).deserialize_array2_with_headers(n_columns)
+    }
+
+    fn deserialize_array2_columns<A: DeserializeOwned>(
+        mut self,
+        columns: &[usize],
+        n_rows: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_columns(columns, n_rows)
+    }
+
+    fn deserialize_array2_skip<A: DeserializeOwned>(
+        mut self,
+        skip_rows: usize,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_skip(skip_rows, shape)
+    }
+
+    fn deserialize_array2_head<A: DeserializeOwned>(
+        mut self,
+        k: usize,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_head(k, n_columns)
+    }
+
+    fn deserialize_array2_tail<A: DeserializeOwned>(
+        mut self,
+        k: usize,
+        n_columns: usize,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_tail(k, n_columns)
+    }
+
+    fn deserialize_array2_with_shape_header<A: DeserializeOwned>(
+        mut self,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_with_shape_header()
+    }
+
+    fn deserialize_array2_map_cells<A: DeserializeOwned, F: FnMut(&str) -> Cow<str>>(
+        mut self,
+        shape: (usize, usize),
+        f: F,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_map_cells(shape, f)
+    }
+
+    fn deserialize_array2_with_progress<A: DeserializeOwned, F: FnMut(usize)>(
+        mut self,
+        shape: (usize, usize),
+        every: usize,
+        on_progress: F,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_with_progress(shape, every, on_progress)
+    }
+
+    fn deserialize_array2_trimmed<A: DeserializeOwned>(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_trimmed(shape)
+    }
+
+    fn deserialize_array2_bool(
+        mut self,
+        shape: (usize, usize),
+        truthy: &[&str],
+        falsy: &[&str],
+    ) -> Result<Array2<bool>, ReadError> {
+        (&mut self).deserialize_array2_bool(shape, truthy, falsy)
+    }
+
+    fn deserialize_array2_chars(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Array2<char>, ReadError> {
+        (&mut self).deserialize_array2_chars(shape)
+    }
+
+    fn deserialize_array2_with_sentinel<A: DeserializeOwned + Clone>(
+        mut self,
+        shape: (usize, usize),
+        sentinel: &str,
+        replacement: A,
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_with_sentinel(shape, sentinel, replacement)
+    }
+
+    fn deserialize_array2_lossy<A: DeserializeOwned + Clone>(
+        mut self,
+        shape: (usize, usize),
+        fill: A,
+    ) -> Result<(Array2<A>, Vec<CellIndex>), ReadError> {
+        (&mut self).deserialize_array2_lossy(shape, fill)
+    }
+
+    fn deserialize_array2_with_index<I: DeserializeOwned, A: DeserializeOwned>(
+        mut self,
+        n_columns: usize,
+    ) -> Result<(Vec<I>, Array2<A>), ReadError> {
+        (&mut self).deserialize_array2_with_index(n_columns)
+    }
+
+    fn deserialize_array2_split<K: DeserializeOwned, A: DeserializeOwned>(
+        mut self,
+        key_columns: usize,
+        n_value_columns: usize,
+    ) -> Result<(Vec<K>, Array2<A>), ReadError> {
+        (&mut self).deserialize_array2_split(key_columns, n_value_columns)
+    }
+
+    fn deserialize_array2_f_order<A: DeserializeOwned>(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_f_order(shape)
+    }
+
+    fn deserialize_array2_transposed<A: DeserializeOwned>(
+        mut self,
+        logical_shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_transposed(logical_shape)
+    }
+
+    fn deserialize_ragged<A: DeserializeOwned>(mut self) -> Result<Vec<Array1<A>>, ReadError> {
+        (&mut self).deserialize_ragged()
+    }
+
+    fn deserialize_columns<A: DeserializeOwned>(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Vec<Array1<A>>, ReadError> {
+        (&mut self).deserialize_columns(shape)
+    }
+
+    fn deserialize_array2_fast<A: FromStr>(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_fast(shape)
+    }
+
+    fn deserialize_array2_u8(mut self, shape: (usize, usize)) -> Result<Array2<u8>, ReadError> {
+        (&mut self).deserialize_array2_u8(shape)
+    }
+
+    fn deserialize_array2_coerce<A: FromStr>(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_coerce(shape)
+    }
+
+    #[cfg(feature = "num-complex")]
+    fn deserialize_array2_complex<A: DeserializeOwned>(
+        mut self,
+        shape_in_complex: (usize, usize),
+    ) -> Result<Array2<Complex<A>>, ReadError> {
+        (&mut self).deserialize_array2_complex(shape_in_complex)
+    }
+
+    #[cfg(feature = "rayon")]
+    fn deserialize_array2_par<A: DeserializeOwned + Send>(
+        mut self,
+        shape: (usize, usize),
+    ) -> Result<Array2<A>, ReadError> {
+        (&mut self).deserialize_array2_par(shape)
+    }
+
+    fn deserialize_array2_into<A: DeserializeOwned>(
+        mut self,
+        out: &mut Array2<A>,
+    ) -> Result<(), ReadError> {
+        (&mut self).deserialize_array2_into(out)
+    }
+
+    fn validate_shape(mut self, expected: (usize, usize)) -> Result<(), ReadError> {
+        (&mut self).validate_shape(expected)
+    }
+
+    fn analyze_columns(mut self, max_rows: usize) -> Result<ColumnHistogram, ReadError> {
+        (&mut self).analyze_columns(max_rows)
+    }
+}
+
+/// Reads CSV data into an ndarray, with settings that can be combined freely
+///
+/// This is an alternative to the more specialized `Array2Reader` methods:
+///
+/// ```rust
+/// # extern crate csv;
+/// # extern crate ndarray;
+/// # extern crate ndarray_csv;
+/// # use csv::ReaderBuilder;
+/// # use ndarray::{array, Array2};
+/// # use ndarray_csv::Array2ReaderBuilder;
+/// # fn main() {
+/// let data = "# exported by some tool\nx, 1, 2\ny, 3\n";
+/// let mut reader = ReaderBuilder::new()
+///     .has_headers(false)
+///     .flexible(true)
+///     .from_reader(data.as_bytes());
+/// let array: Array2<u64> = Array2ReaderBuilder::new(&mut reader)
+///     .skip_rows(1)
+///     .trim(true)
+///     .index_column(true)
+///     .pad_with(0)
+///     .read((2, 2))
+///     .unwrap();
+/// assert_eq!(array, array![[1, 2], [3, 0]]);
+/// # }
+/// ```
+///
+/// More settings may be added in later versions; they will all default to the behavior of
+/// `Array2Reader::deserialize_array2`.
+pub struct Array2ReaderBuilder<'r, R: 'r, A> {
+    reader: &'r mut Reader<R>,
+    skip_rows: usize,
+    trim: bool,
+    index_column: bool,
+    pad: Option<A>,
+    sentinel: Option<(String, A)>,
+}
+
+impl<'r, R: Read, A: DeserializeOwned + Clone> Array2ReaderBuilder<'r, R, A> {
+    /// Start configuring a read from `reader`, with every setting at its default
+    pub fn new(reader: &'r mut Reader<R>) -> Self {
+        Array2ReaderBuilder {
+            reader,
+            skip_rows: 0,
+            trim: false,
+            index_column: false,
+            pad: None,
+            sentinel: None,
+        }
+    }
+
+    /// Discard this many records before reading, e.g. to skip a preamble. Row indices in errors
+    /// count from the first row after the skipped ones.
+    pub fn skip_rows(mut self, skip_rows: usize) -> Self {
+        self.skip_rows = skip_rows;
+        self
+    }
+
+    /// Trim leading and trailing whitespace from each field before parsing it
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Treat the first column as row labels, which are skipped. Column indices in errors still
+    /// count the label column. To keep the labels, use `Array2Reader::deserialize_array2_with_index`.
+    pub fn index_column(mut self, index_column: bool) -> Self {
+        self.index_column = index_column;
+        self
+    }
+
+    /// Pad rows that are too short with `value`. Reading data with rows of different lengths
+    /// requires a `csv::Reader` configured with `flexible(true)`.
+    pub fn pad_with(mut self, value: A) -> Self {
+        self.pad = Some(value);
+        self
+    }
+
+    /// Read fields equal to `sentinel` as `replacement` instead of parsing them
+    pub fn sentinel(mut self, sentinel: &str, replacement: A) -> Self {
+        self.sentinel = Some((sentinel.to_string(), replacement));
+        self
+    }
+
+    /// Read the CSV data into a new ndarray with the given shape
+    pub fn read(self, shape: (usize, usize)) -> Result<Array2<A>, ReadError> {
+        let (n_rows, n_columns) = shape;
+        let mut record = ByteRecord::new();
+        for row_index in 0..self.skip_rows {
+            let more = self
+                .reader
+                .read_byte_record(&mut record)
+                .map_err(|e| ReadError::Csv {
+                    source: Arc::new(e),
+                    at_row_index: row_index,
+                })?;
+            if !more {
+                break;
+            }
+        }
+
+        let first_column = if self.index_column { 1 } else { 0 };
+        let mut records = RecordReader::new(self.reader);
+        records.trim = self.trim;
+        let mut fields = StringRecord::new();
+        let mut field_record = StringRecord::new();
+        let mut values = Vec::with_capacity(reserved_capacity(n_rows, n_columns));
+
+        while records.read()? {
+            let row_index = records.row_index();
+            fields.clear();
+            for field in records.record.iter().skip(first_column) {
+                fields.push_field(field);
+            }
+
+            let mut row_vec: Vec<A> = match self.sentinel {
+                None => deserialize_record(&fields, row_index)
+                    .map_err(|e| map_col_index(e, |i| i + first_column))?,
+                Some((ref sentinel, ref replacement)) => {
+                    let mut row_vec = Vec::with_capacity(fields.len());
+                    for (col_index, field) in fields.iter().enumerate() {
+                        if field == sentinel {
+                            row_vec.push(replacement.clone());
+                        } else {
+                            field_record.clear();
+                            field_record.push_field(field);
+                            let value = deserialize_record(&field_record, row_index)
+                                .map_err(|e| map_col_index(e, |_| col_index + first_column))?;
+                            row_vec.push(value);
+                        }
+                    }
+                    row_vec
+                }
+            };
+
+            if let Some(ref pad) = self.pad {
+                if row_vec.len() < n_columns {
+                    row_vec.resize(n_columns, pad.clone());
+                }
+            }
+            records.check_n_columns(n_columns, row_vec.len())?;
+            values.extend(row_vec);
+        }
+
+        if records.row_count() == n_rows {
+            Ok(Array2::from_shape_vec(shape, values).unwrap())
+        } else {
+            Err(ReadError::NRows {
+                expected: n_rows,
+                actual: records.row_count(),
+            })
+        }
+    }
+}
+
+/// An extension trait; this is implemented by `&mut csv::Reader` and by `csv::Reader`
+///
+/// The CSV data can either be a single row or a single column; both are read into the same 1D
+/// array.
+pub trait Array1Reader {
+    /// Read CSV data into a new 1D ndarray with the given length
+    fn deserialize_array1<A: DeserializeOwned>(self, len: usize) -> Result<Array1<A>, ReadError>;
+
+    /// Read CSV data into a new 1D ndarray, inferring its length
+    fn deserialize_array1_dynamic<A: DeserializeOwned>(self) -> Result<Array1<A>, ReadError>;
+}
+
+impl<R: Read> Array1Reader for &mut Reader<R> {
+    fn deserialize_array1<A: DeserializeOwned>(self, len: usize) -> Result<Array1<A>, ReadError> {
+        let array2 = self.deserialize_array2_dynamic_or_empty()?;
+        let (n_rows, n_columns) = array2.dim();
+        let array1 = into_array1(array2)?;
+        if array1.len() == len {
+            Ok(array1)
+        } else if n_rows == 1 && n_columns > 1 {
+            Err(ReadError::NColumns {
+                at_row_index: 0,
+                expected: len,
+                actual: n_columns,
+                line: None,
+            })
+        } else {
+            Err(ReadError::NRows {
+                expected: len,
+                actual: n_rows,
+            })
+        }
+    }
+
+    fn deserialize_array1_dynamic<A: DeserializeOwned>(self) -> Result<Array1<A>, ReadError> {
+        into_array1(self.deserialize_array2_dynamic_or_empty()?)
+    }
+}
+
+/// This consumes the reader, for when it's only used once
+impl<R: Read> Array1Reader for Reader<R> {
+    fn deserialize_array1<A: DeserializeOwned>(
+        mut self,
+        len: usize,
+    ) -> Result<Array1<A>, ReadError> {
+        (&mut self).deserialize_array1(len)
+    }
+
+    fn deserialize_array1_dynamic<A: DeserializeOwned>(mut self) -> Result<Array1<A>, ReadError> {
+        (&mut self).deserialize_array1_dynamic()
+    }
+}
+
+/// An extension trait; this is implemented by `&mut csv::Reader` and by `csv::Reader`
+pub trait ArrayDReader {
+    /// Read CSV data into a new N-dimensional ndarray with the given shape
+    ///
+    /// Each CSV row holds one lane along the last axis, so the CSV data must have as many columns
+    /// as the length of the last axis and as many rows as the product of the other axes' lengths.
+    fn deserialize_arrayd<A: DeserializeOwned>(
+        self,
+        shape: &[usize],
+    ) -> Result<ArrayD<A>, ReadError>;
+}
+
+impl<R: Read> ArrayDReader for &mut Reader<R> {
+    fn deserialize_arrayd<A: DeserializeOwned>(
+        self,
+        shape: &[usize],
+    ) -> Result<ArrayD<A>, ReadError> {
+        let (n_columns, n_rows) = match shape.split_last() {
+            Some((&last, rest)) => (last, checked_len(rest)?),
+            None => (1, 1),
+        };
+        checked_len(&[n_rows, n_columns])?;
+        let array2 = self.deserialize_array2((n_rows, n_columns))?;
+        // Even with a zero-length axis, ndarray rejects a shape whose other axes overflow `isize`
+        #[allow(deprecated)]
+        array2
+            .into_shape(shape)
+            .map_err(|_| ReadError::ShapeOverflow { n_rows, n_columns })
+    }
+}
+
+/// Multiply the lengths of some axes, failing with `ShapeOverflow` for the first axis whose length
+/// makes the product overflow `usize`
+fn checked_len(lens: &[usize]) -> Result<usize, ReadError> {
+    lens.iter().try_fold(1usize, |n_rows, &n_columns| {
+        n_rows
+            .checked_mul(n_columns)
+            .ok_or(ReadError::ShapeOverflow { n_rows, n_columns })
+    })
+}
+
+/// This consumes the reader, for when it's only used once
+impl<R: Read> ArrayDReader for Reader<R> {
+    fn deserialize_arrayd<A: DeserializeOwned>(
+        mut self,
+        shape: &[usize],
+    ) -> Result<ArrayD<A>, ReadError> {
+        (&mut self).deserialize_arrayd(shape)
+    }
+}
+
+/// An extension trait; this is implemented by `&mut csv::Reader`
+pub trait Array1RowsReader<'r, R> {
+    /// Lazily read CSV data one row at a time, without materializing the whole array
+    ///
+    /// Every row must have the same number of columns as the first row.
+    fn deserialize_rows<A: DeserializeOwned>(self) -> Rows<'r, R, A>;
+}
+
+impl<'r, R: Read> Array1RowsReader<'r, R> for &'r mut Reader<R> {
+    fn deserialize_rows<A: DeserializeOwned>(self) -> Rows<'r, R, A> {
+        Rows {
+            records: RecordReader::new(self),
+            n_columns: None,
+            rows_read: 0,
+            element: PhantomData,
+        }
+    }
+}
+
+/// An iterator over the rows of CSV data; this is created by `deserialize_rows`
+pub struct Rows<'r, R: 'r, A> {
+    records: RecordReader<'r, R>,
+    n_columns: Option<usize>,
+    rows_read: usize,
+    element: PhantomData<A>,
+}
+
+impl<'r, R, A> Rows<'r, R, A> {
+    /// The number of rows that have been read successfully so far
+    ///
+    /// Rows that were returned as errors aren't counted, so after an error this is the number of
+    /// good rows before it, which is useful for checkpointing.
+    pub fn rows_read(&self) -> usize {
+        self.rows_read
+    }
+}
+
+impl<'r, R: Read, A: DeserializeOwned> Iterator for Rows<'r, R, A> {
+    type Item = Result<Array1<A>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.records.read() {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(e) => return Some(Err(e)),
+        }
+        let row_vec: Vec<A> = match self.records.deserialize() {
+            Ok(row_vec) => row_vec,
+            Err(e) => return Some(Err(e)),
+        };
+        let n_columns = *self.n_columns.get_or_insert(row_vec.len());
+        if let Err(e) = self.records.check_n_columns(n_columns, row_vec.len()) {
+            return Some(Err(e));
+        }
+        self.rows_read += 1;
+        Some(Ok(Array1::from(row_vec)))
+    }
+}
+
+/// Flatten an array with a single row or a single column
+fn into_array1<A>(array2: Array2<A>) -> Result<Array1<A>, ReadError> {
+    let (n_rows, n_columns) = array2.dim();
+    if n_rows > 1 && n_columns > 1 {
+        return Err(ReadError::NotOneDimensional { n_rows, n_columns });
+    }
+    #[allow(deprecated)]
+    Ok(array2.into_shape(n_rows * n_columns).unwrap())
+}
+
+/// An extension trait; this is implemented by `&mut csv::Writer`
+pub trait Array2Writer {
+    /// Write this ndarray into CSV format. This accepts owned arrays as well as views, so a window
+    /// into a larger array can be written without copying it.
+    ///
+    /// Elements that serialize as tuples, e.g. `(f64, f64)`, are flattened into one field per
+    /// tuple item, so a 2x3 array of pairs is written as 2 rows of 6 fields. Such data can be read
+    /// back with `Array2Reader::deserialize_array2` and the original 2x3 shape.
+    ///
+    /// Every element is written through the writer's own serializer, so its configuration applies
+    /// uniformly; e.g. with `QuoteStyle::Always`, numbers are quoted just like strings.
+    ///
+    /// The array can have any memory layout, e.g. a transposed or sliced view; it is written the
+    /// same as a standard-layout copy of it would be.
+    fn serialize_array2<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write a header row followed by this ndarray into CSV format
+    ///
+    /// The header row is written regardless of whether the writer was configured with
+    /// `has_headers`. There must be exactly one header per column.
+    fn serialize_array2_with_headers<A: Serialize, S: Data<Elem = A>, H: AsRef<str>>(
+        self,
+        headers: &[H],
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this ndarray into CSV format after converting it to standard (row-major) layout
+    ///
+    /// If the array isn't already in standard layout, e.g. because it was transposed or sliced,
+    /// this makes a copy of the whole array. Prefer `serialize_array2` for arrays which are already
+    /// in standard layout, since it never copies.
+    fn serialize_array2_relayout<A: Serialize + Clone, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this ndarray into CSV format, with `index[i]` prepended to row `i` as a label
+    ///
+    /// There must be exactly one label per row.
+    fn serialize_array2_with_index<A: Serialize, S: Data<Elem = A>, I: Serialize>(
+        self,
+        index: &[I],
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this ndarray into CSV format, using `fmt` to turn each element into the text of its
+    /// field, e.g. `|x| format!("{:.4}", x)` to write floats with four decimal places
+    ///
+    /// The formatted fields are quoted by the writer as usual, so they may safely contain
+    /// delimiters or quotes.
+    fn serialize_array2_formatted<A, S: Data<Elem = A>, F: Fn(&A) -> String>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        fmt: F,
+    ) -> Result<(), WriteError>;
+
+    /// Write this ndarray into CSV format, writing each element with its `Display` impl instead of
+    /// serde, for types that implement `Display` but not `Serialize`
+    ///
+    /// As with `serialize_array2_formatted`, fields that contain delimiters or quotes are quoted.
+    fn serialize_array2_display<A: Display, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this `f32` or `f64` ndarray into CSV format, writing `nan`, `inf` and `neg_inf` in
+    /// place of NaN, positive infinity and negative infinity, e.g. `"NA"`, `"Infinity"` and
+    /// `"-Infinity"`
+    ///
+    /// Finite values are written exactly as `serialize_array2` writes them.
+    fn serialize_array2_with_nan_repr<A: Serialize + Copy + Into<f64>, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        nan: &str,
+        inf: &str,
+        neg_inf: &str,
+    ) -> Result<(), WriteError>;
+
+    /// Write this `f64` ndarray into CSV format, rounding each value to `sig_figs` significant
+    /// figures regardless of its magnitude
+    ///
+    /// Values are written in positional notation and keep their trailing zeros, so at 3
+    /// significant figures `123456.0` is written as `123000`, `0.00012345` as `0.000123` and `1.0`
+    /// as `1.00`. NaN and the infinities are written as `NaN`, `inf` and `-inf`. A `sig_figs` of 0
+    /// is a `ZeroSigFigs` error; nothing is written in that case.
+    fn serialize_array2_sigfigs<S: Data<Elem = f64>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        sig_figs: usize,
+    ) -> Result<(), WriteError>;
+
+    /// Write this boolean ndarray into CSV format, writing `truthy` for `true` and `falsy` for
+    /// `false`
+    fn serialize_array2_bool<S: Data<Elem = bool>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        truthy: &str,
+        falsy: &str,
+    ) -> Result<(), WriteError>;
+
+    /// Write a single row into CSV format, so that an array can be written one row at a time
+    /// without ever holding all of it in memory
+    ///
+    /// Nothing is checked about the number of columns; the writer rejects rows of different
+    /// lengths unless it was configured with `flexible(true)`. Unlike the other methods, this
+    /// doesn't flush the writer, so call `flush` after writing the last row.
+    fn serialize_row<A: Serialize, S: Data<Elem = A>>(
+        self,
+        row: &ArrayBase<S, Ix1>,
+    ) -> Result<(), WriteError>;
+
+    /// Write each row yielded by `rows` into CSV format, e.g. rows that are computed on the fly,
+    /// without building an `Array2` first
+    ///
+    /// As with `serialize_row`, the writer rejects rows of different lengths unless it was
+    /// configured with `flexible(true)`. The writer is flushed after the last row.
+    fn serialize_rows<A: Serialize, I: IntoIterator<Item = Array1<A>>>(
+        self,
+        rows: I,
+    ) -> Result<(), WriteError>;
+
+    /// Write row-major data from a flat slice into CSV format, with `n_columns` values per row
+    ///
+    /// This is for data that is already contiguous in standard layout, e.g. from
+    /// `Array2::as_slice`, and writes each row straight from the slice. The slice's length must be
+    /// a multiple of `n_columns`.
+    fn serialize_array2_slice<A: Serialize>(
+        self,
+        data: &[A],
+        n_columns: usize,
+    ) -> Result<(), WriteError>;
+
+    /// Write only the rows of this ndarray that are in `range` into CSV format, e.g. to write a
+    /// preview of a large array without copying it
+    ///
+    /// A range that reaches past the last row, or that starts after it ends, is a `RowRange`
+    /// error; nothing is written in that case.
+    fn serialize_array2_rows<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        range: Range<usize>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this complex ndarray into CSV format, with each element as two adjacent fields, the
+    /// real part followed by the imaginary part
+    ///
+    /// `Array2Reader::deserialize_array2_complex` reads this back. This requires the `num-complex`
+    /// feature.
+    #[cfg(feature = "num-complex")]
+    fn serialize_array2_complex<A: Serialize, S: Data<Elem = Complex<A>>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this `f64` ndarray into CSV format, formatting each value with `ryu` as the shortest
+    /// string that parses back to exactly the same value, e.g. `0.1` and `1e-7`
+    ///
+    /// The output is the same on every platform, so files written this way can be checked into
+    /// version control without spurious diffs. NaN and the infinities are written as `NaN`, `inf`
+    /// and `-inf`, which `str::parse` reads back. This requires the `ryu` feature.
+    #[cfg(feature = "ryu")]
+    fn serialize_array2_ryu<S: Data<Elem = f64>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this ndarray into CSV format, preceded by a shape header record such as `2,3` that
+    /// `Array2Reader::deserialize_array2_with_shape_header` can read back
+    ///
+    /// The header is padded with empty fields to the width of the rows. The header of an array with
+    /// a single column is a single field such as `2x1` instead, so that it's no wider than the
+    /// rows. As with `serialize_array2`, an array with no columns is written as empty records,
+    /// which can't be read back.
+    fn serialize_array2_with_shape_header<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError>;
+
+    /// Write this ndarray into CSV format, followed by a trailer line such as `# rows=2 cols=3`
+    /// that records its shape, where `comment_prefix` is `"#"` in this example
+    ///
+    /// A reader built with `csv::ReaderBuilder::comment` set to the first byte of `comment_prefix`
+    /// skips the trailer. It is written as a record whose first field is the text and whose other
+    /// fields are empty, so that it has as many fields as the rows do. For the text to stay a
+    /// comment it must not be quoted, so the writer must use the default `QuoteStyle::Necessary`
+    /// without a `comment` setting of its own, and `comment_prefix` must not contain the delimiter.
+    fn serialize_array2_with_trailer<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        comment_prefix: &str,
+    ) -> Result<(), WriteError>;
+}
+
+/// An error while writing an ndarray into CSV format
+///
+/// Before version 0.6, the writer methods returned a bare `csv::Error`. Those errors are now wrapped
+/// in `WriteError::Csv`; code that matched on a `csv::Error` should match on that variant instead,
+/// and code that only propagates errors with `?` into a `Box<dyn Error>` keeps working unchanged.
+///
+/// Version 0.6 also fixed transposed and sliced arrays being written as empty fields. There is no
+/// error for an array's memory layout: each row is serialized by iterating over its elements in
+/// logical order, so arrays of every layout are written correctly, as `.to_owned()` copies would be.
+#[derive(Debug)]
+pub enum WriteError {
+    Csv(csv::Error),
+    /// The number of headers doesn't match the number of columns in the array
+    NHeaders {
+        expected: usize,
+        actual: usize,
+    },
+    /// The number of index labels doesn't match the number of rows in the array
+    NIndex {
+        expected: usize,
+        actual: usize,
+    },
+    /// The CSV output was supposed to be a string, but it isn't valid UTF-8
+    Utf8(FromUtf8Error),
+    /// An N-dimensional array needs at least 2 dimensions to be written as CSV
+    TooFewDimensions {
+        ndim: usize,
+    },
+    /// The length of a flat slice of data isn't a multiple of the number of columns
+    SliceLength {
+        len: usize,
+        n_columns: usize,
+    },
+    /// The range of rows to write isn't within the array
+    RowRange {
+        start: usize,
+        end: usize,
+        n_rows: usize,
+    },
+    /// Values can't be rounded to zero significant figures
+    ZeroSigFigs,
+}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            WriteError::Csv(csv_error) => csv_error.fmt(f),
+            WriteError::NHeaders { expected, actual } => write!(
+                f,
+                "Expected {} headers but got {} headers",
+                expected, actual
+            ),
+            WriteError::NIndex { expected, actual } => write!(
+                f,
+                "Expected {} index labels but got {} index labels",
+                expected, actual
+            ),
+            WriteError::Utf8(utf8_error) => utf8_error.fmt(f),
+            WriteError::TooFewDimensions { ndim } => write!(
+                f,
+                "Expected at least 2 dimensions but got {} dimensions",
+                ndim
+            ),
+            WriteError::SliceLength { len, n_columns } => write!(
+                f,
+                "A slice of length {} can't be split into rows of {} columns",
+                len, n_columns
+            ),
+            WriteError::RowRange { start, end, n_rows } => write!(
+                f,
+                "The rows {}..{} aren't within an array with {} rows",
+                start, end, n_rows
+            ),
+            WriteError::ZeroSigFigs => {
+                write!(f, "The number of significant figures must be at least 1")
+            }
+        }
+    }
+}
+
+impl Error for WriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WriteError::Csv(csv_error) => Some(csv_error),
+            WriteError::Utf8(utf8_error) => Some(utf8_error),
+            _ => None,
+        }
+    }
+}
+
+impl From<csv::Error> for WriteError {
+    fn from(csv_error: csv::Error) -> Self {
+        WriteError::Csv(csv_error)
+    }
+}
+
+impl<W: Write> Array2Writer for &mut Writer<W> {
+    fn serialize_array2<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        for row in array.outer_iter() {
+            let row_iter = Row1DIter::new(&row);
+            self.serialize(&row_iter)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_array2_with_headers<A: Serialize, S: Data<Elem = A>, H: AsRef<str>>(
+        self,
+        headers: &[H],
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        if headers.len() != array.ncols() {
+            return Err(WriteError::NHeaders {
+                expected: array.ncols(),
+                actual: headers.len(),
+            });
+        }
+        // Serializing the headers (rather than using write_record) means that csv's automatic
+        // header handling is used up by this record, so it won't run again for the data rows
+        let header_record: Vec<&str> = headers.iter().map(AsRef::as_ref).collect();
+        self.serialize(header_record)?;
+        self.serialize_array2(array)
+    }
+
+    fn serialize_array2_relayout<A: Serialize + Clone, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        self.serialize_array2(&array.as_standard_layout())
+    }
+
+    fn serialize_array2_with_index<A: Serialize, S: Data<Elem = A>, I: Serialize>(
+        self,
+        index: &[I],
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        if index.len() != array.nrows() {
+            return Err(WriteError::NIndex {
+                expected: array.nrows(),
+                actual: index.len(),
+            });
+        }
+        for (label, row) in index.iter().zip(array.outer_iter()) {
+            let row_iter = Row1DIter::new(&row);
+            // csv flattens the nested sequence into a single record
+            self.serialize((label, &row_iter))?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_array2_with_nan_repr<A: Serialize + Copy + Into<f64>, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        nan: &str,
+        inf: &str,
+        neg_inf: &str,
+    ) -> Result<(), WriteError> {
+        for row in array.outer_iter() {
+            let fields: Vec<FloatField<A>> = row
+                .iter()
+                .map(|&x| {
+                    let x64: f64 = x.into();
+                    if x64.is_nan() {
+                        FloatField::Token(nan)
+                    } else if x64 == f64::INFINITY {
+                        FloatField::Token(inf)
+                    } else if x64 == f64::NEG_INFINITY {
+                        FloatField::Token(neg_inf)
+                    } else {
+                        FloatField::Value(x)
+                    }
+                })
+                .collect();
+            self.serialize(fields)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_array2_display<A: Display, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        self.serialize_array2_formatted(array, |x| format!("{}", x))
+    }
+
+    fn serialize_array2_sigfigs<S: Data<Elem = f64>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        sig_figs: usize,
+    ) -> Result<(), WriteError> {
+        if sig_figs == 0 {
+            return Err(WriteError::ZeroSigFigs);
+        }
+        self.serialize_array2_formatted(array, |&x| format_sig_figs(x, sig_figs))
+    }
+
+    fn serialize_array2_formatted<A, S: Data<Elem = A>, F: Fn(&A) -> String>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        fmt: F,
+    ) -> Result<(), WriteError> {
+        for row in array.outer_iter() {
+            self.write_record(row.iter().map(&fmt))?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_array2_bool<S: Data<Elem = bool>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        truthy: &str,
+        falsy: &str,
+    ) -> Result<(), WriteError> {
+        for row in array.outer_iter() {
+            self.write_record(row.iter().map(|&x| if x { truthy } else { falsy }))?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_row<A: Serialize, S: Data<Elem = A>>(
+        self,
+        row: &ArrayBase<S, Ix1>,
+    ) -> Result<(), WriteError> {
+        let view = row.view();
+        let row_iter = Row1DIter::new(&view);
+        self.serialize(&row_iter)?;
+        Ok(())
+    }
+
+    fn serialize_rows<A: Serialize, I: IntoIterator<Item = Array1<A>>>(
+        self,
+        rows: I,
+    ) -> Result<(), WriteError> {
+        for row in rows {
+            let view = row.view();
+            let row_iter = Row1DIter::new(&view);
+            self.serialize(&row_iter)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_array2_slice<A: Serialize>(
+        self,
+        data: &[A],
+        n_columns: usize,
+    ) -> Result<(), WriteError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        if n_columns == 0 || data.len() % n_columns != 0 {
+            return Err(WriteError::SliceLength {
+                len: data.len(),
+                n_columns,
+            });
+        }
+        for row in data.chunks(n_columns) {
+            self.serialize(row)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_array2_rows<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        range: Range<usize>,
+    ) -> Result<(), WriteError> {
+        if range.start > range.end || range.end > array.nrows() {
+            return Err(WriteError::RowRange {
+                start: range.start,
+                end: range.end,
+                n_rows: array.nrows(),
+            });
+        }
+        for row in array.outer_iter().skip(range.start).take(range.len()) {
+            let row_iter = Row1DIter::new(&row);
+            self.serialize(&row_iter)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "num-complex")]
+    fn serialize_array2_complex<A: Serialize, S: Data<Elem = Complex<A>>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        for row in array.outer_iter() {
+            // Each tuple is written as two fields
+            let pairs: Vec<(&A, &A)> = row.iter().map(|c| (&c.re, &c.im)).collect();
+            self.serialize(pairs)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ryu")]
+    fn serialize_array2_ryu<S: Data<Elem = f64>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        let mut buffer = ryu::Buffer::new();
+        for row in array.outer_iter() {
+            for &x in row.iter() {
+                self.write_field(buffer.format(x))?;
+            }
+            // Terminate the record
+            self.write_record(None::<&[u8]>)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    fn serialize_array2_with_shape_header<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+    ) -> Result<(), WriteError> {
+        let (n_rows, n_columns) = (array.nrows().to_string(), array.ncols().to_string());
+        if array.ncols() < 2 {
+            // A single field keeps the header as narrow as the rows
+            self.write_record(&[format!("{}x{}", n_rows, n_columns)])?;
+        } else {
+            let padding = vec![""; array.ncols() - 2];
+            self.write_record(
+                [n_rows.as_str(), n_columns.as_str()]
+                    .iter()
+                    .cloned()
+                    .chain(padding),
+            )?;
+        }
+        self.serialize_array2(array)
+    }
+
+    fn serialize_array2_with_trailer<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix2>,
+        comment_prefix: &str,
+    ) -> Result<(), WriteError> {
+        self.serialize_array2(array)?;
+        let trailer = format!(
+            "{} rows={} cols={}",
+            comment_prefix,
+            array.nrows(),
+            array.ncols()
+        );
+        let padding = vec![""; array.ncols().saturating_sub(1)];
+        self.write_record(once(trailer.as_str()).chain(padding))?;
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+/// Write an ndarray into CSV format with the settings from `builder`, like
+/// `Array2Writer::serialize_array2`, but without a record terminator after the last row
+///
+/// A `csv::Writer` always ends each record with a terminator, so this writes the last row into a
+/// buffer with a separate writer and strips the terminator from it. The underlying writer is
+/// returned afterwards.
+pub fn write_array2_no_trailing_newline<A: Serialize, S: Data<Elem = A>, W: Write>(
+    builder: &WriterBuilder,
+    mut wtr: W,
+    array: &ArrayBase<S, Ix2>,
+) -> Result<W, WriteError> {
+    if array.nrows() == 0 {
+        return Ok(wtr);
+    }
+    let (head, last) = array.view().split_at(Axis(0), array.nrows() - 1);
+    builder.from_writer(&mut wtr).serialize_array2(&head)?;
+
+    let mut last_writer = builder.from_writer(vec![]);
+    last_writer.serialize_array2(&last)?;
+    let mut bytes = last_writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+
+    let terminator = record_terminator(builder)?;
+    if bytes.ends_with(&terminator) {
+        bytes.truncate(bytes.len() - terminator.len());
+    }
+
+    wtr.write_all(&bytes)
+        .and_then(|()| wtr.flush())
+        .map_err(csv::Error::from)?;
+    Ok(wtr)
+}
+
+/// Write several ndarrays into CSV format with the settings from `builder`, separated by blank
+/// lines, so that `read_array2_blocks` can read them back
+///
+/// Each blank line is the builder's record terminator on its own. A `csv::Writer` can't write one,
+/// since it quotes an empty record, so each array is written with a separate writer. This means
+/// that the arrays can have different numbers of columns, but then reading them back needs a reader
+/// configured with `flexible(true)`. The underlying writer is returned afterwards.
+pub fn write_array2_blocks<A: Serialize, S: Data<Elem = A>, W: Write>(
+    builder: &WriterBuilder,
+    mut wtr: W,
+    arrays: &[ArrayBase<S, Ix2>],
+) -> Result<W, WriteError> {
+    let terminator = record_terminator(builder)?;
+    for (index, array) in arrays.iter().enumerate() {
+        if index > 0 {
+            wtr.write_all(&terminator).map_err(csv::Error::from)?;
+        }
+        builder.from_writer(&mut wtr).serialize_array2(array)?;
+    }
+    wtr.flush().map_err(csv::Error::from)?;
+    Ok(wtr)
+}
+
+/// Format `x` in positional notation, rounded to `sig_figs` significant figures
+///
+/// The rounding is done by `{:e}` formatting, which rounds the exact binary value; the digits and
+/// exponent are then rearranged, so no further floating-point error is introduced.
+fn format_sig_figs(x: f64, sig_figs: usize) -> String {
+    if !x.is_finite() {
+        return x.to_string();
+    }
+    let scientific = format!("{:.*e}", sig_figs - 1, x);
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+    let exponent: i32 = exponent[1..].parse().unwrap();
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits = mantissa.replace('.', "");
+
+    let mut formatted = sign.to_string();
+    if exponent < 0 {
+        formatted.push_str("0.");
+        formatted.push_str(&"0".repeat((-exponent - 1) as usize));
+        formatted.push_str(&digits);
+    } else {
+        let n_integer_digits = exponent as usize + 1;
+        if n_integer_digits >= digits.len() {
+            formatted.push_str(&digits);
+            formatted.push_str(&"0".repeat(n_integer_digits - digits.len()));
+        } else {
+            formatted.push_str(&digits[..n_integer_digits]);
+            formatted.push('.');
+            formatted.push_str(&digits[n_integer_digits..]);
+        }
+    }
+    formatted
+}
+
+/// The bytes that a writer from `builder` ends each record with
+fn record_terminator(builder: &WriterBuilder) -> Result<Vec<u8>, WriteError> {
+    // An empty record is written as a pair of quote characters followed by the configured
+    // terminator, which is how the terminator is found out
+    let mut terminator_writer = builder.from_writer(vec![]);
+    terminator_writer.write_record(None::<&[u8]>)?;
+    let mut empty_record = terminator_writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(empty_record.split_off(2))
+}
+
+/// The UTF-8 byte order mark, which `write_array2_excel` writes before the CSV data
+pub const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Write an ndarray into CSV format with the settings from `builder`, like
+/// `Array2Writer::serialize_array2`, but starting with a UTF-8 byte order mark
+///
+/// Excel needs the byte order mark to detect that a CSV file is UTF-8 rather than the system's
+/// legacy encoding. A `csv::Writer` doesn't give access to its underlying writer, so the byte order
+/// mark is written before the `csv::Writer` is built. The underlying writer is returned afterwards.
+pub fn write_array2_excel<A: Serialize, S: Data<Elem = A>, W: Write>(
+    builder: &WriterBuilder,
+    mut wtr: W,
+    array: &ArrayBase<S, Ix2>,
+) -> Result<W, WriteError> {
+    wtr.write_all(UTF8_BOM).map_err(csv::Error::from)?;
+    builder.from_writer(&mut wtr).serialize_array2(array)?;
+    Ok(wtr)
+}
+
+/// Read a headerless CSV file into a new ndarray with the given shape
+///
+/// For other reader settings, use `Array2Reader` with a `csv::Reader`.
+pub fn read_array2_from_path<A: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    shape: (usize, usize),
+) -> Result<Array2<A>, ReadError> {
+    let file = File::open(path).map_err(|e| ReadError::Io(Arc::new(e)))?;
+    ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(file)
+        .deserialize_array2(shape)
+}
+
+/// Read a headerless, gzip-compressed CSV file into a new ndarray with the given shape
+///
+/// The file is decompressed as it is read, so the decompressed data is never all in memory at
+/// once. This requires the `flate2` feature.
+#[cfg(feature = "flate2")]
+pub fn read_array2_from_gz_path<A: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    shape: (usize, usize),
+) -> Result<Array2<A>, ReadError> {
+    let file = File::open(path).map_err(|e| ReadError::Io(Arc::new(e)))?;
+    ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(GzDecoder::new(file))
+        .deserialize_array2(shape)
+}
+
+/// Read a headerless CSV file into a new ndarray with the given shape by memory-mapping it
+///
+/// The operating system pages the file in as it is read, rather than the file being copied into
+/// buffers, which can reduce memory pressure for very large files. This requires the `memmap2`
+/// feature.
+///
+/// Memory-mapping is only sound if the file isn't changed while it is mapped. If another process
+/// truncates or writes to the file during the read, the data read may be inconsistent, and on some
+/// platforms the process may crash with a bus error. Only use this for files that nothing else is
+/// writing to.
+#[cfg(feature = "memmap2")]
+pub fn read_array2_from_mmap_path<A: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    shape: (usize, usize),
+) -> Result<Array2<A>, ReadError> {
+    let file = File::open(path).map_err(|e| ReadError::Io(Arc::new(e)))?;
+    // Safety: this relies on the caller not changing the file while it is mapped, as documented
+    let mmap = unsafe { Mmap::map(&file) }.map_err(|e| ReadError::Io(Arc::new(e)))?;
+    array2_from_bytes(&mmap, shape)
+}
+
+/// An error from `validate_and_reshape`
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReshapeError {
+    /// The wrong number of values was passed
+    NValues {
+        /// The number of values that the shape needs
+        expected: usize,
+        actual: usize,
+    },
+    /// The shape has more elements than can be counted, so no array can have it
+    ShapeOverflow { n_rows: usize, n_columns: usize },
+}
+
+#[cfg(feature = "alloc")]
+impl Display for ReshapeError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            ReshapeError::NValues { expected, actual } => {
+                write!(f, "Expected {} values but got {} values", expected, actual)
+            }
+            ReshapeError::ShapeOverflow { n_rows, n_columns } => write!(
+                f,
+                "The shape {}x{} has too many elements",
+                n_rows, n_columns
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Error for ReshapeError {}
+
+/// Turn row-major values into a new ndarray with the given shape, checking that there are exactly
+/// enough of them
+///
+/// This is for callers that parse CSV some other way, e.g. on targets without `std`: it only uses
+/// ndarray and `Vec`, not `std::io` or the `csv` crate. This crate as a whole still needs `std`,
+/// because `csv` does. This requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub fn validate_and_reshape<A>(
+    values: Vec<A>,
+    shape: (usize, usize),
+) -> Result<Array2<A>, ReshapeError> {
+    let (n_rows, n_columns) = shape;
+    let overflow = ReshapeError::ShapeOverflow { n_rows, n_columns };
+    let expected = n_rows.checked_mul(n_columns).ok_or(overflow)?;
+    if values.len() == expected {
+        // A `Vec` of zero-sized values can be longer than ndarray allows
+        Array2::from_shape_vec(shape, values).map_err(|_| overflow)
+    } else {
+        Err(ReshapeError::NValues {
+            expected,
+            actual: values.len(),
+        })
+    }
+}
+
+/// The delimiters tried by `detect_and_read_array2`, in order
+pub const DETECTED_DELIMITERS: [u8; 3] = [b',', b';', b'\t'];
+
+/// Read CSV data whose delimiter is not known in advance, returning the array and the delimiter
+///
+/// Each delimiter in `DETECTED_DELIMITERS` is tried in turn, seeking back to the reader's starting
+/// position before each attempt, and the first one whose rows all have `shape.1` columns and parse
+/// successfully is returned. All other settings come from `reader_builder`, whose delimiter is
+/// left set to the last delimiter that was tried. If no delimiter works, the error from the first
+/// one (a comma) is returned.
+pub fn detect_and_read_array2<A: DeserializeOwned, R: Read + Seek>(
+    reader_builder: &mut ReaderBuilder,
+    mut reader: R,
+    shape: (usize, usize),
+) -> Result<(Array2<A>, u8), ReadError> {
+    let start = reader
+        .stream_position()
+        .map_err(|e| ReadError::Io(Arc::new(e)))?;
+    let mut first_error = None;
+    for &delimiter in DETECTED_DELIMITERS.iter() {
+        reader
+            .seek(SeekFrom::Start(start))
+            .map_err(|e| ReadError::Io(Arc::new(e)))?;
+        match reader_builder
+            .delimiter(delimiter)
+            .from_reader(&mut reader)
+            .deserialize_array2(shape)
+        {
+            Ok(array) => return Ok((array, delimiter)),
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    Err(first_error.unwrap())
+}
+
+/// Read CSV data that contains several arrays separated by blank lines into one new ndarray per
+/// array, inferring their shapes, with the settings from `reader_builder`
+///
+/// Each block of rows must be rectangular, but different blocks can have different numbers of
+/// columns, as long as the builder is configured with `flexible(true)`. Any number of blank lines
+/// separates two blocks, but comment lines don't. Row indices in errors count the rows of all of
+/// the blocks.
+///
+/// A `csv::Reader` skips blank lines without reporting them, so this makes its own reader, which
+/// keeps the raw bytes of each record to look for blank lines before it. Lines may end in `\r\n`,
+/// `\r` or `\n`, as with the default `csv::Terminator::CRLF`.
+pub fn read_array2_blocks<A: DeserializeOwned, R: Read>(
+    reader_builder: &ReaderBuilder,
+    reader: R,
+) -> Result<Vec<Array2<A>>, ReadError> {
+    let mut csv_reader = reader_builder.from_reader(RecordingRead {
+        inner: reader,
+        bytes: Vec::new(),
+        start: 0,
+    });
+    let mut records = RecordReader::new(&mut csv_reader);
+    let mut blocks = Vec::new();
+    let mut block = DynamicRows::new();
+    let mut after_cr = false;
+    while records.read()? {
+        let end = records.reader.position().byte();
+        let raw = records.reader.get_mut().take_until(end);
+        let mut raw = &raw[..];
+        // The reader stops after the `\r` of a `\r\n`, so the `\n` comes before the next record
+        if after_cr {
+            raw = raw.strip_prefix(b"\n").unwrap_or(raw);
+        }
+        after_cr = raw.last() == Some(&b'\r');
+
+        if block.n_rows > 0 && blank_line_before(raw, &records.record) {
+            blocks.push(block.into_array());
+            block = DynamicRows::new();
+        }
+        block.push(&records)?;
+    }
+    if block.n_rows > 0 {
+        blocks.push(block.into_array());
+    }
+    Ok(blocks)
+}
+
+/// Reads from `inner`, keeping the bytes that have been read since `start`
+struct RecordingRead<R> {
+    inner: R,
+    bytes: Vec<u8>,
+    /// The offset in the data of the first byte in `bytes`
+    start: u64,
+}
+
+impl<R> RecordingRead<R> {
+    /// Take the kept bytes up to the offset `end`
+    fn take_until(&mut self, end: u64) -> Vec<u8> {
+        let len = (end - self.start) as usize;
+        self.start = end;
+        self.bytes.drain(..len).collect()
+    }
+}
+
+impl<R: Read> Read for RecordingRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Whether `raw`, the bytes from the end of the previous record to the end of `record`, has a
+/// blank line before the lines of `record` itself
+///
+/// Only blank lines and comments can come before the record, which takes up the last lines: one,
+/// plus one for each line break inside its quoted fields.
+fn blank_line_before(raw: &[u8], record: &StringRecord) -> bool {
+    let line_breaks = |text: &[u8]| {
+        let crlfs = text.windows(2).filter(|pair| pair == b"\r\n").count();
+        text.iter().filter(|&&b| b == b'\r' || b == b'\n').count() - crlfs
+    };
+    let record_lines = 1 + record
+        .iter()
+        .map(|field| line_breaks(field.as_bytes()))
+        .sum::<usize>();
+    let lines = split_lines(raw);
+    lines[..lines.len().saturating_sub(record_lines)]
+        .iter()
+        .any(|line| line.is_empty())
+}
+
+/// Split `bytes` into lines ending in `\r\n`, `\r` or `\n`, without their line breaks. A line break at
+/// the end doesn't start another line.
+fn split_lines(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\r' || bytes[i] == b'\n' {
+            lines.push(&bytes[start..i]);
+            if bytes[i] == b'\r' && bytes.get(i + 1) == Some(&b'\n') {
+                i += 1;
+            }
+            start = i + 1;
+        }
+        i += 1;
+    }
+    if start < bytes.len() {
+        lines.push(&bytes[start..]);
+    }
+    lines
+}
+
+/// Read headerless CSV data from a string into a new ndarray with the given shape
+pub fn array2_from_str<A: DeserializeOwned>(
+    s: &str,
+    shape: (usize, usize),
+) -> Result<Array2<A>, ReadError> {
+    array2_from_bytes(s.as_bytes(), shape)
+}
+
+/// Read headerless CSV data from lines of text into a new ndarray with the given shape, e.g. lines
+/// that have already been filtered in a way that the `csv::Reader` options don't cover
+///
+/// Each line is followed by a `\n` as it is read, so the lines shouldn't include their own
+/// line endings. The lines are read as they are needed rather than all being joined first.
+pub fn array2_from_lines<A: DeserializeOwned, I: IntoIterator<Item = String>>(
+    lines: I,
+    shape: (usize, usize),
+) -> Result<Array2<A>, ReadError> {
+    ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(LinesRead {
+            lines: lines.into_iter(),
+            line: Vec::new(),
+            pos: 0,
+        })
+        .deserialize_array2(shape)
+}
+
+/// Reads lines of text as if they were one text with a `\n` after each line
+struct LinesRead<I> {
+    lines: I,
+    /// The current line, including its `\n`
+    line: Vec<u8>,
+    /// How much of the current line has been read
+    pos: usize,
+}
+
+impl<I: Iterator<Item = String>> Read for LinesRead<I> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.line.len() {
+            match self.lines.next() {
+                Some(line) => {
+                    self.line = line.into_bytes();
+                    self.line.push(b'\n');
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.line.len() - self.pos);
+        buf[..n].copy_from_slice(&self.line[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Read headerless CSV data from bytes into a new ndarray with the given shape
+pub fn array2_from_bytes<A: DeserializeOwned>(
+    bytes: &[u8],
+    shape: (usize, usize),
+) -> Result<Array2<A>, ReadError> {
+    ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(bytes)
+        .deserialize_array2(shape)
+}
+
+/// Write an ndarray into a headerless CSV file, replacing the file if it exists
+///
+/// For other writer settings, use `Array2Writer` with a `csv::Writer`.
+pub fn write_array2_to_path<A: Serialize, S: Data<Elem = A>, P: AsRef<Path>>(
+    path: P,
+    array: &ArrayBase<S, Ix2>,
+) -> Result<(), WriteError> {
+    WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)?
+        .serialize_array2(array)
+}
+
+/// Append the rows of an ndarray to a headerless CSV file, creating the file if it doesn't exist,
+/// e.g. to log successive batches of data
+///
+/// The existing contents of the file aren't read, so it is up to the caller to append arrays with
+/// the same number of columns as the rows that are already there. The file must end with a record
+/// terminator, as files written by this crate do.
+pub fn append_array2_to_path<A: Serialize, S: Data<Elem = A>, P: AsRef<Path>>(
+    path: P,
+    array: &ArrayBase<S, Ix2>,
+) -> Result<(), WriteError> {
+    let file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .map_err(csv::Error::from)?;
+    WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(file)
+        .serialize_array2(array)
+}
+
+/// Write an ndarray into a headerless, gzip-compressed CSV file, replacing the file if it exists
+///
+/// This requires the `flate2` feature.
+#[cfg(feature = "flate2")]
+pub fn write_array2_to_gz_path<A: Serialize, S: Data<Elem = A>, P: AsRef<Path>>(
+    path: P,
+    array: &ArrayBase<S, Ix2>,
+) -> Result<(), WriteError> {
+    let file = File::create(path).map_err(csv::Error::from)?;
+    let mut writer = WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(GzEncoder::new(file, Compression::default()));
+    writer.serialize_array2(array)?;
+    // The gzip stream is only valid once the encoder has written its footer
+    let encoder = writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+    encoder.finish().map_err(csv::Error::from)?;
+    Ok(())
+}
+
+/// Write an ndarray into a headerless CSV string
+pub fn array2_to_string<A: Serialize, S: Data<Elem = A>>(
+    array: &ArrayBase<S, Ix2>,
+) -> Result<String, WriteError> {
+    String::from_utf8(array2_to_bytes(array)?).map_err(WriteError::Utf8)
+}
+
+/// Write an ndarray into headerless CSV bytes
+///
+/// These are the same bytes that `Array2Writer::serialize_array2` writes with a headerless
+/// `csv::Writer`. Unlike `array2_to_string`, they aren't required to be UTF-8.
+pub fn array2_to_bytes<A: Serialize, S: Data<Elem = A>>(
+    array: &ArrayBase<S, Ix2>,
+) -> Result<Vec<u8>, WriteError> {
+    let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+    writer.serialize_array2(array)?;
+    writer
+        .into_inner()
+        .map_err(|e| WriteError::Csv(csv::Error::from(e.into_error())))
+}
+
+/// This wraps the iterator for a row so that we can implement Serialize.
+///
+/// Serialize is not implemented for iterators: https://github.com/serde-rs/serde/issues/571
+///
+/// This solution from Hyeonu wraps the iterator:
+/// https://users.rust-lang.org/t/how-to-serialize-an-iterator-to-json/59272/3
+///
+/// The iterator is cloned each time the row is serialized, because `csv` serializes a record
+/// twice when the writer is configured with `has_headers(true)`: once to infer a header row and
+/// once to write it.
+struct Row1DIter<'b, B: 'b>(Iter<'b, B, Dim<[usize; 1]>>);
+
+impl<'b, B> Row1DIter<'b, B> {
+    fn new(row: &'b ArrayView1<B>) -> Self {
+        Row1DIter(row.iter())
+    }
+}
+
+impl<'b, B> Serialize for Row1DIter<'b, B>
+where
+    B: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.clone())
+    }
+}
+
+/// A field written by `Array2Writer::serialize_array2_with_nan_repr`: either a finite value, which
+/// is serialized as usual, or the token that replaces a non-finite one
+enum FloatField<'t, A> {
+    Value(A),
+    Token(&'t str),
+}
+
+impl<'t, A: Serialize> Serialize for FloatField<'t, A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            FloatField::Value(value) => value.serialize(serializer),
+            FloatField::Token(token) => serializer.serialize_str(token),
+        }
+    }
+}
+
+/// An extension trait; this is implemented by `&mut csv::Writer`
+pub trait ArrayDWriter {
+    /// Write this N-dimensional ndarray into CSV format
+    ///
+    /// The last axis becomes the columns, and all of the other axes are folded into rows in
+    /// row-major order, so an array of shape `[2, 3, 4]` is written as 6 rows of 4 columns. The
+    /// array must have at least 2 dimensions.
+    fn serialize_arrayd<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, IxDyn>,
+    ) -> Result<(), WriteError>;
+}
+
+impl<W: Write> ArrayDWriter for &mut Writer<W> {
+    fn serialize_arrayd<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, IxDyn>,
+    ) -> Result<(), WriteError> {
+        if array.ndim() < 2 {
+            return Err(WriteError::TooFewDimensions { ndim: array.ndim() });
+        }
+        for row in array.lanes(Axis(array.ndim() - 1)) {
+            let row_iter = Row1DIter::new(&row);
+            self.serialize(&row_iter)?;
+        }
+        self.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+/// How a 1D array is laid out in CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// All elements on a single line
+    Row,
+    /// One element per line
+    Column,
+}
+
+impl Default for Orientation {
+    /// A single row, which is what `serialize_array2` writes for an array of shape `(1, n)`
+    fn default() -> Self {
+        Orientation::Row
+    }
+}
+
+/// An extension trait; this is implemented by `&mut csv::Writer`
+pub trait Array1Writer {
+    /// Write this 1D ndarray into CSV format as either a single row or a single column
+    fn serialize_array1<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix1>,
+        orientation: Orientation,
+    ) -> Result<(), WriteError>;
+}
+
+impl<W: Write> Array1Writer for &mut Writer<W> {
+    fn serialize_array1<A: Serialize, S: Data<Elem = A>>(
+        self,
+        array: &ArrayBase<S, Ix1>,
+        orientation: Orientation,
+    ) -> Result<(), WriteError> {
+        let axis = match orientation {
+            Orientation::Row => Axis(0),
+            Orientation::Column => Axis(1),
+        };
+        self.serialize_array2(&array.view().insert_axis(axis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadError::*;
+    use super::*;
+    use csv::{QuoteStyle, Reader, ReaderBuilder, Terminator, Trim, WriterBuilder};
+    use ndarray::Array;
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    fn in_memory_reader(content: &'static str) -> Reader<impl Read> {
+        ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(Cursor::new(content))
+    }
+
+    fn test_reader() -> Reader<impl Read> {
+        in_memory_reader("1,2,3\n4,5,6\n")
+    }
+
+    /// A path in the temp directory that's unique to this test run
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ndarray-csv-{}-{}", std::process::id(), name))
+    }
+
+    fn flexible_reader(content: &'static str) -> Reader<impl Read> {
+        ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(Cursor::new(content))
+    }
+
+    #[test]
+    fn test_read_float() {
+        let actual: Array2<f64> = test_reader().deserialize_array2((2, 3)).unwrap();
+        let expected = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_integer() {
+        let actual: Array2<u64> = test_reader().deserialize_array2((2, 3)).unwrap();
+        let expected = array![[1, 2, 3], [4, 5, 6]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_owned_and_borrowed() {
+        let expected = array![[1, 2, 3], [4, 5, 6]];
+
+        let owned = test_reader();
+        let actual: Array2<u64> = owned.deserialize_array2((2, 3)).unwrap();
+        assert_eq!(actual, expected);
+
+        let mut borrowed = test_reader();
+        let actual: Array2<u64> = (&mut borrowed).deserialize_array2((2, 3)).unwrap();
+        assert_eq!(actual, expected);
+        assert!(borrowed.is_done());
+    }
+
+    #[test]
+    fn test_read_optional() {
+        let actual: Array2<Option<f64>> = in_memory_reader("1,,3\n,5,6\n")
+            .deserialize_array2((2, 3))
+            .unwrap();
+        let expected = array![[Some(1.0), None, Some(3.0)], [None, Some(5.0), Some(6.0)]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_empty_cell_without_option() {
+        assert_matches! {
+            in_memory_reader("1,,3\n,5,6\n").deserialize_array2::<f64>((2, 3)).unwrap_err(),
+            Parse { at_row_index: 0, at_col_index: 1, .. }
+        }
+    }
+
+    #[test]
     fn test_read_dynamic() {
         let actual: Array2<u64> = test_reader().deserialize_array2_dynamic().unwrap();
         let expected = array![[1, 2, 3], [4, 5, 6]];
@@ -246,71 +4092,2191 @@ mod tests {
     }
 
     #[test]
-    fn test_read_csv_error() {
-        in_memory_reader("1,2,3\n4,x,6\n")
-            .deserialize_array2::<i8>((2, 3))
-            .unwrap_err();
+    fn test_read_dynamic_expected_width_from_first_row() {
+        assert_matches! {
+            flexible_reader("1,2\n3,4\n5,6\n7,8,9\n").deserialize_array2_dynamic::<u64>().unwrap_err(),
+            NColumns { at_row_index: 3, expected: 2, actual: 3, .. }
+        }
+        assert_matches! {
+            flexible_reader("1,2,3\n4,5,6\n7\n").deserialize_array2_dynamic::<u64>().unwrap_err(),
+            NColumns { at_row_index: 2, expected: 3, actual: 1, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_dynamic_empty() {
+        assert_matches! {
+            in_memory_reader("").deserialize_array2_dynamic::<u64>().unwrap_err(),
+            Empty
+        }
+        let actual: Array2<u64> = in_memory_reader("")
+            .deserialize_array2_dynamic_or_empty()
+            .unwrap();
+        assert_eq!(actual.dim(), (0, 0));
+    }
+
+    #[test]
+    fn test_read_rows_dynamic() {
+        let actual: Array2<u64> = test_reader().deserialize_array2_rows_dynamic(3).unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+
+        // The first row has the expected width, but a later one doesn't
+        assert_matches! {
+            flexible_reader("1,2\n3,4\n5,6,7\n").deserialize_array2_rows_dynamic::<u64>(2).unwrap_err(),
+            NColumns { at_row_index: 2, expected: 2, actual: 3, line: Some(3) }
+        }
+        assert_matches! {
+            flexible_reader("1,2,3\n4,5,6\n").deserialize_array2_rows_dynamic::<u64>(2).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 2, actual: 3, line: Some(1) }
+        }
+    }
+
+    #[test]
+    fn test_read_square() {
+        let actual: Array2<u64> = in_memory_reader("1,2,3\n4,5,6\n7,8,9\n")
+            .deserialize_array2_square()
+            .unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+
+        assert_matches! {
+            in_memory_reader("1,2,3,4\n5,6,7,8\n9,10,11,12\n")
+                .deserialize_array2_square::<u64>()
+                .unwrap_err(),
+            NotSquare { rows: 3, cols: 4 }
+        }
+    }
+
+    #[test]
+    fn test_read_dynamic_comments() {
+        let reader = |content: &'static str| {
+            ReaderBuilder::new()
+                .has_headers(false)
+                .comment(Some(b'#'))
+                .flexible(true)
+                .from_reader(Cursor::new(content))
+        };
+        let actual: Array2<u64> = reader("# header\n1,2\n3,4")
+            .deserialize_array2_dynamic()
+            .unwrap();
+        assert_eq!(actual, array![[1, 2], [3, 4]]);
+
+        assert_matches! {
+            reader("# header\n1,2\n# note\n3\n")
+                .deserialize_array2_dynamic::<u64>()
+                .unwrap_err(),
+            NColumns { at_row_index: 1, expected: 2, actual: 1, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_dynamic_report() {
+        let (actual, report) = in_memory_reader("1,2,\n3,4,5\n")
+            .deserialize_array2_dynamic_report::<Option<u64>>()
+            .unwrap();
+        assert_eq!(
+            actual,
+            array![[Some(1), Some(2), None], [Some(3), Some(4), Some(5)]]
+        );
+        assert_eq!(
+            report,
+            ShapeReport {
+                n_rows: 2,
+                n_columns: 3,
+                min_fields: 3,
+                max_fields: 3,
+                rows_with_trailing_empty_field: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_dynamic_capped() {
+        let actual: Array2<u64> = test_reader().deserialize_array2_dynamic_capped(2).unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+
+        assert_matches! {
+            test_reader().deserialize_array2_dynamic_capped::<u64>(1).unwrap_err(),
+            TooManyRows { max: 1 }
+        }
+    }
+
+    #[test]
+    fn test_read_total() {
+        let actual: Array2<u64> = in_memory_reader("1,2\n3,4\n5,6\n")
+            .deserialize_array2_total(6)
+            .unwrap();
+        assert_eq!(actual, array![[1, 2], [3, 4], [5, 6]]);
+
+        assert_eq!(
+            in_memory_reader("1,2\n3,4\n5,6\n")
+                .deserialize_array2_total::<u64>(5)
+                .unwrap_err(),
+            TotalElements {
+                total_elements: 5,
+                n_columns: 2
+            }
+        );
+        assert_matches! {
+            in_memory_reader("1,2\n3,4\n").deserialize_array2_total::<u64>(6).unwrap_err(),
+            NRows { expected: 3, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_dynamic_checked() {
+        let (actual, warnings) = flexible_reader("1,2,\n3,4\n5,6,\n")
+            .deserialize_array2_dynamic_checked::<u64>()
+            .unwrap();
+        assert_eq!(actual, array![[1, 2], [3, 4], [5, 6]]);
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::TrailingEmptyField { at_row_index: 0 },
+                Warning::TrailingEmptyField { at_row_index: 2 }
+            ]
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "Row 2 had a trailing empty field that was dropped"
+        );
+
+        let (_, warnings) = test_reader()
+            .deserialize_array2_dynamic_checked::<u64>()
+            .unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_read_blocks() {
+        fn read_blocks<A: DeserializeOwned>(
+            builder: &mut ReaderBuilder,
+            content: &'static str,
+        ) -> Result<Vec<Array2<A>>, ReadError> {
+            read_array2_blocks(
+                builder.has_headers(false).flexible(true),
+                content.as_bytes(),
+            )
+        }
+
+        let content = "1,2,3\n4,5,6\n\n7,8\n\"9\n\",10\n\r\n\n11\n";
+        let actual: Vec<Array2<String>> = read_blocks(&mut ReaderBuilder::new(), content).unwrap();
+        let expected: Vec<Array2<String>> = vec![
+            array![["1", "2", "3"], ["4", "5", "6"]].mapv(String::from),
+            array![["7", "8"], ["9\n", "10"]].mapv(String::from),
+            array![["11"]].mapv(String::from),
+        ];
+        assert_eq!(actual, expected);
+
+        // Within a block, the rows must all be the same width
+        assert_matches! {
+            read_blocks::<u64>(&mut ReaderBuilder::new(), "1,2\n3\n").unwrap_err(),
+            NColumns { at_row_index: 1, expected: 2, actual: 1, .. }
+        }
+
+        // The last block doesn't need a line break at its end
+        let actual: Vec<Array2<u64>> =
+            read_blocks(&mut ReaderBuilder::new(), "1,2\n3,4\n\n5").unwrap();
+        assert_eq!(actual, vec![array![[1, 2], [3, 4]], array![[5]]]);
+
+        // A comment line doesn't separate blocks
+        let actual: Vec<Array2<u64>> = read_blocks(
+            ReaderBuilder::new().comment(Some(b'#')),
+            "1,2\n# note\n3,4\n",
+        )
+        .unwrap();
+        assert_eq!(actual, vec![array![[1, 2], [3, 4]]]);
+
+        // Any of the line breaks that csv accepts can end a line
+        let actual: Vec<Array2<u64>> = read_blocks(
+            &mut ReaderBuilder::new(),
+            "1,2\r\n3,4\r\n\r\n5,6\r\n\r\n7\r\r8\r",
+        )
+        .unwrap();
+        assert_eq!(
+            actual,
+            vec![
+                array![[1, 2], [3, 4]],
+                array![[5, 6]],
+                array![[7]],
+                array![[8]]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_dynamic_with_order() {
+        let expected = array![[1, 2, 3], [4, 5, 6]];
+
+        let c: Array2<u64> = test_reader()
+            .deserialize_array2_dynamic_with_order(Order::RowMajor)
+            .unwrap();
+        assert_eq!(c, expected);
+        assert!(c.is_standard_layout());
+
+        let f: Array2<u64> = test_reader()
+            .deserialize_array2_dynamic_with_order(Order::ColumnMajor)
+            .unwrap();
+        assert_eq!(f, expected);
+        assert!(f.t().is_standard_layout());
+
+        assert_matches! {
+            in_memory_reader("").deserialize_array2_dynamic_with_order::<u64>(Order::ColumnMajor),
+            Err(Empty)
+        }
+    }
+
+    #[test]
+    fn test_read_dynamic_max_cols() {
+        let actual: Array2<u64> = test_reader()
+            .deserialize_array2_dynamic_max_cols(3)
+            .unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+
+        // The wide row fails before its unparseable fields are looked at
+        assert_eq!(
+            in_memory_reader("1,x,y\n")
+                .deserialize_array2_dynamic_max_cols::<u64>(2)
+                .unwrap_err(),
+            TooManyColumns {
+                at_row_index: 0,
+                max: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_dynamic_partial() {
+        let (actual, error) = test_reader().deserialize_array2_dynamic_partial::<u64>();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(error, None);
+
+        let (actual, error) =
+            flexible_reader("1,2\n3,4\n5\n6,7\n").deserialize_array2_dynamic_partial::<u64>();
+        assert_eq!(actual, array![[1, 2], [3, 4]]);
+        assert_eq!(
+            error,
+            Some(NColumns {
+                at_row_index: 2,
+                expected: 2,
+                actual: 1,
+                line: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_with_shape() {
+        let (actual, shape) = test_reader()
+            .deserialize_array2_with_shape::<u64>()
+            .unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(shape, (2, 3));
+    }
+
+    #[test]
+    fn test_read_with_shape_trailing_commas() {
+        // A trailing comma adds an empty column
+        let (actual, shape) = in_memory_reader("1,2,\n3,4,\n")
+            .deserialize_array2_with_shape::<Option<u64>>()
+            .unwrap();
+        assert_eq!(
+            actual,
+            array![[Some(1), Some(2), None], [Some(3), Some(4), None]]
+        );
+        assert_eq!(shape, (2, 3));
+
+        // ...so inconsistent trailing commas give inconsistent column counts, which the non-flexible
+        // csv reader rejects by itself
+        assert_matches! {
+            in_memory_reader("1,2,\n3,4\n")
+                .deserialize_array2_with_shape::<Option<u64>>()
+                .unwrap_err(),
+            Csv { at_row_index: 1, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_csv_error() {
+        // Without `flexible(true)`, csv itself rejects rows of different lengths
+        let error = in_memory_reader("1,2,3\n4,5\n")
+            .deserialize_array2::<i8>((2, 3))
+            .unwrap_err();
+        assert_matches!(
+            error,
+            Csv {
+                at_row_index: 1,
+                ..
+            }
+        );
+        assert!(error.to_string().starts_with("On row 1, "));
+    }
+
+    #[test]
+    fn test_clone_error() {
+        let error = in_memory_reader("1,2,3\n4,5\n")
+            .deserialize_array2::<i8>((2, 3))
+            .unwrap_err();
+        let clone = error.clone();
+        assert_eq!(clone, error);
+        assert_eq!(clone.to_string(), error.to_string());
+        assert!(clone.source().is_some());
+
+        let errors = vec![
+            NRows {
+                expected: 2,
+                actual: 1,
+            },
+            Empty,
+        ];
+        assert_eq!(errors.clone(), errors);
+    }
+
+    #[test]
+    fn test_read_parse_error() {
+        let error = in_memory_reader("1,2,3\n4,x,6\n")
+            .deserialize_array2::<i8>((2, 3))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Parse {
+                at_row_index: 1,
+                at_col_index: 1,
+                value: "x".to_string(),
+            }
+        );
+
+        assert_eq!(
+            in_memory_reader("1,2,3\n4,5,x\n")
+                .deserialize_array2_columns::<i8>(&[2, 0], 2)
+                .unwrap_err(),
+            Parse {
+                at_row_index: 1,
+                at_col_index: 2,
+                value: "x".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_error_source() {
+        let error = in_memory_reader("1,2,3\n4,5\n")
+            .deserialize_array2::<i8>((2, 3))
+            .unwrap_err();
+        let source = error.source().unwrap();
+        assert_matches!(
+            source.downcast_ref::<csv::Error>().unwrap().kind(),
+            csv::ErrorKind::UnequalLengths { .. }
+        );
+
+        assert!(test_reader()
+            .deserialize_array2::<i8>((3, 3))
+            .unwrap_err()
+            .source()
+            .is_none());
+    }
+
+    #[test]
+    fn test_read_too_few_rows() {
+        assert_matches! {
+            test_reader().deserialize_array2::<i8>((3, 3)).unwrap_err(),
+            NRows { expected: 3, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_huge_shape() {
+        // The shape isn't trusted enough to preallocate it, and its size mustn't overflow
+        assert_matches! {
+            in_memory_reader("1,2\n").deserialize_array2::<u64>((usize::MAX / 2, 2)).unwrap_err(),
+            NRows { actual: 1, .. }
+        }
+        assert_matches! {
+            in_memory_reader("1,2\n").deserialize_array2::<u64>((1 << 40, 2)).unwrap_err(),
+            NRows { expected: 1099511627776, actual: 1 }
+        }
+        assert_matches! {
+            in_memory_reader("1,2\n").deserialize_array2::<u64>((usize::MAX / 2, 4)).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 4, actual: 2, .. }
+        }
+
+        // Every reader that reserves space for its shape caps the reservation
+        let huge = (1 << 40, 2);
+        let reader = || in_memory_reader("1,2\n");
+        let wrong_n_rows = |error: ReadError| assert_matches!(error, NRows { actual: 1, .. });
+        wrong_n_rows(reader().deserialize_array2_fast::<u64>(huge).unwrap_err());
+        wrong_n_rows(reader().deserialize_array2_u8(huge).unwrap_err());
+        wrong_n_rows(
+            reader()
+                .deserialize_array2_f_order::<u64>(huge)
+                .unwrap_err(),
+        );
+        wrong_n_rows(reader().deserialize_columns::<u64>(huge).unwrap_err());
+        wrong_n_rows(
+            reader()
+                .deserialize_array2_trimmed::<u64>(huge)
+                .unwrap_err(),
+        );
+        wrong_n_rows(
+            reader()
+                .deserialize_array2_columns::<u64>(&[1, 0], huge.0)
+                .unwrap_err(),
+        );
+        wrong_n_rows(
+            reader()
+                .deserialize_array2_map_cells::<u64, _>(huge, |field| Cow::Borrowed(field))
+                .unwrap_err(),
+        );
+        wrong_n_rows(
+            reader()
+                .deserialize_array2_with_sentinel(huge, "NA", 0u64)
+                .unwrap_err(),
+        );
+        wrong_n_rows(reader().deserialize_array2_lossy(huge, 0u64).unwrap_err());
+        wrong_n_rows(
+            Array2ReaderBuilder::<_, u64>::new(&mut reader())
+                .read(huge)
+                .unwrap_err(),
+        );
+        #[cfg(feature = "rayon")]
+        wrong_n_rows(reader().deserialize_array2_par::<u64>(huge).unwrap_err());
+        #[cfg(feature = "num-complex")]
+        wrong_n_rows(
+            reader()
+                .deserialize_array2_complex::<u64>((1 << 40, 1))
+                .unwrap_err(),
+        );
+        assert_matches! {
+            reader().deserialize_array2_head::<u64>(usize::MAX, 1 << 40).unwrap_err(),
+            NColumns { expected: 1099511627776, actual: 2, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_zero_columns() {
+        // With no columns there are no values, so only the number of rows can be wrong
+        assert_matches! {
+            in_memory_reader("").deserialize_array2::<u64>((2, 0)).unwrap_err(),
+            NRows { expected: 2, actual: 0 }
+        }
+        assert_matches! {
+            in_memory_reader("").deserialize_array2_fast::<u64>((2, 0)).unwrap_err(),
+            NRows { expected: 2, actual: 0 }
+        }
+    }
+
+    #[test]
+    fn test_read_too_many_rows() {
+        assert_matches! {
+            test_reader().deserialize_array2::<i8>((1, 3)).unwrap_err(),
+            NRows { expected: 1, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_error_eq() {
+        assert_eq!(
+            test_reader().deserialize_array2::<i8>((3, 3)).unwrap_err(),
+            NRows {
+                expected: 3,
+                actual: 2
+            }
+        );
+        assert_ne!(
+            test_reader().deserialize_array2::<i8>((3, 3)).unwrap_err(),
+            NRows {
+                expected: 3,
+                actual: 3
+            }
+        );
+
+        // Csv errors are compared by row and kind
+        let parse_error = || {
+            in_memory_reader("1,2,3\n4,x,6\n")
+                .deserialize_array2::<i8>((2, 3))
+                .unwrap_err()
+        };
+        assert_eq!(parse_error(), parse_error());
+        assert_ne!(
+            parse_error(),
+            in_memory_reader("1,x,3\n4,5,6\n")
+                .deserialize_array2::<i8>((2, 3))
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_read_too_few_columns() {
+        assert_matches! {
+            test_reader().deserialize_array2::<i8>((2, 4)).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 4, actual: 3, line: Some(1) }
+        }
+    }
+
+    #[test]
+    fn test_read_too_many_columns() {
+        assert_matches! {
+            test_reader().deserialize_array2::<i8>((2, 2)).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 2, actual: 3, line: Some(1) }
+        }
+    }
+
+    #[test]
+    fn test_read_n_columns_line() {
+        // The quoted field spans two lines, so the short row starts on line 3
+        let error = flexible_reader("1,\"2\n\"\n3\n")
+            .deserialize_array2_dynamic::<String>()
+            .unwrap_err();
+        assert_matches! {
+            error,
+            NColumns { at_row_index: 1, expected: 2, actual: 1, line: Some(3) }
+        }
+        assert_eq!(
+            error.to_string(),
+            "On row 1 (line 3), expected 2 columns but got 1 columns"
+        );
+    }
+
+    #[test]
+    fn test_read_trim() {
+        let reader = ReaderBuilder::new()
+            .has_headers(false)
+            .trim(Trim::Fields)
+            .from_reader(Cursor::new(" 1 , 2 \n"));
+        let actual: Array2<u64> = reader.deserialize_array2((1, 2)).unwrap();
+        assert_eq!(actual, array![[1, 2]]);
+    }
+
+    #[test]
+    fn test_read_trimmed() {
+        let mut reader = ReaderBuilder::new().from_reader(Cursor::new(" a , b \n 1 , 2 \n"));
+        let actual: Array2<u64> = (&mut reader).deserialize_array2_trimmed((1, 2)).unwrap();
+        assert_eq!(actual, array![[1, 2]]);
+        assert_eq!(reader.headers().unwrap(), vec![" a ", " b "]);
+    }
+
+    #[test]
+    fn test_read_bool() {
+        let actual = in_memory_reader("Y,N\ny,1\n")
+            .deserialize_array2_bool((2, 2), &["Y", "y", "1"], &["N", "n", "0"])
+            .unwrap();
+        assert_eq!(actual, array![[true, false], [true, true]]);
+
+        assert_eq!(
+            in_memory_reader("Y,N\nY,maybe\n")
+                .deserialize_array2_bool((2, 2), &["Y"], &["N"])
+                .unwrap_err(),
+            Parse {
+                at_row_index: 1,
+                at_col_index: 1,
+                value: "maybe".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_shape() {
+        assert_eq!(test_reader().validate_shape((2, 3)), Ok(()));
+        assert_eq!(
+            test_reader().validate_shape((3, 3)).unwrap_err(),
+            test_reader().deserialize_array2::<u64>((3, 3)).unwrap_err()
+        );
+        assert_eq!(
+            test_reader().validate_shape((2, 2)).unwrap_err(),
+            test_reader().deserialize_array2::<u64>((2, 2)).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_analyze_columns() {
+        let content = "1,2\n3,4,5\n6,7\n8\n9,10,11\n";
+        let histogram = flexible_reader(content).analyze_columns(100).unwrap();
+        let counts: BTreeMap<usize, usize> = vec![(1, 1), (2, 2), (3, 2)].into_iter().collect();
+        assert_eq!(
+            histogram,
+            ColumnHistogram {
+                counts,
+                n_rows: 5,
+                widest_row: Some(1),
+                truncated: false,
+            }
+        );
+        assert_eq!(histogram.max_columns(), 3);
+
+        let histogram = flexible_reader(content).analyze_columns(2).unwrap();
+        assert_eq!(histogram.n_rows, 2);
+        assert!(histogram.truncated);
+    }
+
+    #[test]
+    fn test_read_with_sentinel() {
+        let actual: Array2<f64> = in_memory_reader("1.5,-999\n-999,4\n")
+            .deserialize_array2_with_sentinel((2, 2), "-999", f64::NAN)
+            .unwrap();
+        assert_eq!(actual[(0, 0)], 1.5);
+        assert!(actual[(0, 1)].is_nan());
+        assert!(actual[(1, 0)].is_nan());
+        assert_eq!(actual[(1, 1)], 4.0);
+
+        assert_eq!(
+            in_memory_reader("1,NA\n3,x\n")
+                .deserialize_array2_with_sentinel::<f64>((2, 2), "NA", 0.0)
+                .unwrap_err(),
+            Parse {
+                at_row_index: 1,
+                at_col_index: 1,
+                value: "x".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_head() {
+        let mut reader = in_memory_reader("1,2\n3,4\n5,6\nx,y,z\n");
+        let head: Array2<u64> = (&mut reader).deserialize_array2_head(2, 2).unwrap();
+        assert_eq!(head, array![[1, 2], [3, 4]]);
+
+        // The rest of the data is still there to be read
+        let next: Array2<u64> = (&mut reader).deserialize_array2_head(1, 2).unwrap();
+        assert_eq!(next, array![[5, 6]]);
+
+        let all: Array2<u64> = test_reader().deserialize_array2_head(10, 3).unwrap();
+        assert_eq!(all, array![[1, 2, 3], [4, 5, 6]]);
+
+        assert_matches! {
+            test_reader().deserialize_array2_head::<u64>(1, 2).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 2, actual: 3, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_tail() {
+        let tail: Array2<u64> = in_memory_reader("1,2\n3,4\n5,6\n7,8\n")
+            .deserialize_array2_tail(2, 2)
+            .unwrap();
+        assert_eq!(tail, array![[5, 6], [7, 8]]);
+
+        let all: Array2<u64> = test_reader().deserialize_array2_tail(10, 3).unwrap();
+        assert_eq!(all, array![[1, 2, 3], [4, 5, 6]]);
+
+        // Rows that don't end up in the tail are still checked
+        assert_matches! {
+            in_memory_reader("1,x\n3,4\n")
+                .deserialize_array2_tail::<u64>(1, 2)
+                .unwrap_err(),
+            Parse { at_row_index: 0, at_col_index: 1, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_with_shape_header() {
+        let actual: Array2<u64> = in_memory_reader("2,3,\n1,2,3\n4,5,6\n")
+            .deserialize_array2_with_shape_header()
+            .unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(
+            in_memory_reader("2,x,\n1,2,3\n4,5,6\n")
+                .deserialize_array2_with_shape_header::<u64>()
+                .unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 1,
+                value: "x".to_string()
+            }
+        );
+        assert_eq!(
+            in_memory_reader("2,3,4\n1,2,3\n4,5,6\n")
+                .deserialize_array2_with_shape_header::<u64>()
+                .unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 2,
+                value: "4".to_string()
+            }
+        );
+        assert_matches! {
+            in_memory_reader("3,3,\n1,2,3\n4,5,6\n")
+                .deserialize_array2_with_shape_header::<u64>()
+                .unwrap_err(),
+            NRows { expected: 3, actual: 2 }
+        }
+
+        let actual: Array2<u64> = in_memory_reader("2x1\n1\n2\n")
+            .deserialize_array2_with_shape_header()
+            .unwrap();
+        assert_eq!(actual, array![[1], [2]]);
+        assert_eq!(
+            in_memory_reader("2\n1\n2\n")
+                .deserialize_array2_with_shape_header::<u64>()
+                .unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 0,
+                value: "2".to_string()
+            }
+        );
+
+        // The header is untrusted, so a huge shape is an error rather than a huge allocation
+        assert_eq!(
+            in_memory_reader("99999999999,99999999999,\n1,2,3\n")
+                .deserialize_array2_with_shape_header::<u64>()
+                .unwrap_err(),
+            ShapeOverflow {
+                n_rows: 99999999999,
+                n_columns: 99999999999
+            }
+        );
+        assert_matches! {
+            in_memory_reader("4294967296,3,\n1,2,3\n4,5,6\n")
+                .deserialize_array2_with_shape_header::<u64>()
+                .unwrap_err(),
+            NRows { expected: 4294967296, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_map_cells() {
+        let content = "\"1.234,56\";7\n0,5;\"-2.000\"\n";
+        let mut seen = Vec::new();
+        let actual: Array2<f64> = ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(b';')
+            .from_reader(Cursor::new(content))
+            .deserialize_array2_map_cells((2, 2), |field| {
+                seen.push(field.to_string());
+                Cow::Owned(field.replace('.', "").replace(',', "."))
+            })
+            .unwrap();
+        assert_eq!(actual, array![[1234.56, 7.0], [0.5, -2000.0]]);
+        assert_eq!(seen, vec!["1.234,56", "7", "0,5", "-2.000"]);
+
+        // Parse errors show the text that was actually parsed
+        assert_eq!(
+            in_memory_reader("1,2\n")
+                .deserialize_array2_map_cells::<u64, _>((1, 2), |field| {
+                    Cow::Owned(format!("{}x", field))
+                })
+                .unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 0,
+                value: "1x".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_lossy() {
+        let (actual, filled) = in_memory_reader("1.5,oops,3\n4,5,\n")
+            .deserialize_array2_lossy((2, 3), -1.0)
+            .unwrap();
+        assert_eq!(actual, array![[1.5, -1.0, 3.0], [4.0, 5.0, -1.0]]);
+        assert_eq!(filled, vec![(0, 1), (1, 2)]);
+
+        // The shape is still checked
+        assert_matches! {
+            test_reader().deserialize_array2_lossy((3, 3), 0.0).unwrap_err(),
+            NRows { expected: 3, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_with_progress() {
+        let content = "1\n2\n3\n4\n5\n";
+        let mut reported = Vec::new();
+        let actual: Array2<u64> = in_memory_reader(content)
+            .deserialize_array2_with_progress((5, 1), 2, |rows| reported.push(rows))
+            .unwrap();
+        assert_eq!(actual, array![[1], [2], [3], [4], [5]]);
+        assert_eq!(reported, vec![2, 4, 5]);
+
+        // The final count isn't repeated when it falls on a multiple of `every`
+        let mut reported = Vec::new();
+        in_memory_reader(content)
+            .deserialize_array2_with_progress::<u64, _>((5, 1), 5, |rows| reported.push(rows))
+            .unwrap();
+        assert_eq!(reported, vec![5]);
+    }
+
+    #[test]
+    fn test_read_out_of_range() {
+        for &(content, value) in
+            [("1,128\n", "128"), ("1,255\n", "255"), ("1,-129\n", "-129")].iter()
+        {
+            let error = in_memory_reader(content)
+                .deserialize_array2::<i8>((1, 2))
+                .unwrap_err();
+            assert_eq!(
+                error,
+                OutOfRange {
+                    at_row_index: 0,
+                    at_col_index: 1,
+                    value: value.to_string(),
+                }
+            );
+        }
+        assert_eq!(
+            OutOfRange {
+                at_row_index: 2,
+                at_col_index: 1,
+                value: "300".to_string(),
+            }
+            .to_string(),
+            "On row 2, column 1, the value 300 is out of range for the element type"
+        );
+
+        // Anything else that isn't an integer is still a parse error
+        assert_matches! {
+            in_memory_reader("1,x\n").deserialize_array2::<i8>((1, 2)).unwrap_err(),
+            Parse { .. }
+        }
+    }
+
+    #[test]
+    fn test_read_ragged() {
+        let actual: Vec<Array1<u64>> = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(Cursor::new("1,2\n3\n\n4,5,6\n"))
+            .deserialize_ragged()
+            .unwrap();
+        assert_eq!(actual, vec![array![1, 2], array![3], array![4, 5, 6]]);
+
+        assert_eq!(
+            ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(Cursor::new("1,2\n3,x,5\n"))
+                .deserialize_ragged::<u64>()
+                .unwrap_err(),
+            Parse {
+                at_row_index: 1,
+                at_col_index: 1,
+                value: "x".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_transposed() {
+        let actual: Array2<u64> = in_memory_reader("1,4\n2,5\n3,6\n")
+            .deserialize_array2_transposed((2, 3))
+            .unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+
+        // The shape is checked against the CSV data as it is written
+        assert_matches! {
+            test_reader().deserialize_array2_transposed::<u64>((2, 3)).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 2, actual: 3, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_u8() {
+        let actual = in_memory_reader("0,7,255\n010,99,128\n")
+            .deserialize_array2_u8((2, 3))
+            .unwrap();
+        assert_eq!(actual, array![[0, 7, 255], [10, 99, 128]]);
+
+        assert_eq!(
+            in_memory_reader("1,256\n")
+                .deserialize_array2_u8((1, 2))
+                .unwrap_err(),
+            OutOfRange {
+                at_row_index: 0,
+                at_col_index: 1,
+                value: "256".to_string()
+            }
+        );
+        for &field in ["", "-1", "+1", " 1", "x", "1.0"].iter() {
+            let content = format!("0,{}\n", field);
+            assert_eq!(
+                ReaderBuilder::new()
+                    .has_headers(false)
+                    .from_reader(Cursor::new(content))
+                    .deserialize_array2_u8((1, 2))
+                    .unwrap_err(),
+                Parse {
+                    at_row_index: 0,
+                    at_col_index: 1,
+                    value: field.to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_coerce() {
+        let actual: Array2<f64> = in_memory_reader("42,1.5,-2\n7,0.25,3e2\n")
+            .deserialize_array2_coerce((2, 3))
+            .unwrap();
+        assert_eq!(actual, array![[42.0, 1.5, -2.0], [7.0, 0.25, 300.0]]);
+
+        // Whole numbers written as floats are read into integer types
+        let actual: Array2<i32> = in_memory_reader("42,1.0,-2.00\n7,0,3.\n")
+            .deserialize_array2_coerce((2, 3))
+            .unwrap();
+        assert_eq!(actual, array![[42, 1, -2], [7, 0, 3]]);
+
+        for &field in ["1.5", "1.01", "1e2", ".0"].iter() {
+            let content = format!("0,{}\n", field);
+            assert_eq!(
+                ReaderBuilder::new()
+                    .has_headers(false)
+                    .from_reader(Cursor::new(content))
+                    .deserialize_array2_coerce::<i32>((1, 2))
+                    .unwrap_err(),
+                Parse {
+                    at_row_index: 0,
+                    at_col_index: 1,
+                    value: field.to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_floats() {
+        let actual: Array2<f64> =
+            in_memory_reader("NaN,nan,NAN\ninf,Inf,-Infinity\n+inf,-INF,1.5\n")
+                .deserialize_array2((3, 3))
+                .unwrap();
+        assert!(actual.row(0).iter().all(|x| x.is_nan()));
+        assert_eq!(
+            actual.slice(s![1.., ..]),
+            array![
+                [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY],
+                [f64::INFINITY, f64::NEG_INFINITY, 1.5]
+            ]
+        );
+
+        assert_eq!(
+            in_memory_reader("1,NA\n")
+                .deserialize_array2::<f32>((1, 2))
+                .unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 1,
+                value: "NA".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_builder() {
+        let mut reader = test_reader();
+        let actual: Array2<u64> = Array2ReaderBuilder::new(&mut reader).read((2, 3)).unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+
+        let mut reader = flexible_reader("preamble\na,1,NA\nb,3\nc,x,6\n");
+        let error = Array2ReaderBuilder::new(&mut reader)
+            .skip_rows(1)
+            .index_column(true)
+            .pad_with(0)
+            .sentinel("NA", 9)
+            .read((3, 2))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Parse {
+                at_row_index: 2,
+                at_col_index: 1,
+                value: "x".to_string(),
+            }
+        );
+
+        let mut reader = flexible_reader("a,1,NA\nb,3\n");
+        let actual: Array2<u64> = Array2ReaderBuilder::new(&mut reader)
+            .index_column(true)
+            .pad_with(0)
+            .sentinel("NA", 9)
+            .read((2, 2))
+            .unwrap();
+        assert_eq!(actual, array![[1, 9], [3, 0]]);
+    }
+
+    #[test]
+    fn test_read_chars() {
+        let actual = in_memory_reader("A,C\nG,T\n")
+            .deserialize_array2_chars((2, 2))
+            .unwrap();
+        assert_eq!(actual, array![['A', 'C'], ['G', 'T']]);
+
+        assert_eq!(
+            in_memory_reader("A,C\nGT,T\n")
+                .deserialize_array2_chars((2, 2))
+                .unwrap_err(),
+            Parse {
+                at_row_index: 1,
+                at_col_index: 0,
+                value: "GT".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_padded() {
+        let actual: Array2<u64> = flexible_reader("1,2,3\n4\n5,6\n")
+            .deserialize_array2_padded(3)
+            .unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 0, 0], [5, 6, 0]]);
+    }
+
+    #[test]
+    fn test_read_padded_too_long() {
+        assert_matches! {
+            flexible_reader("1,2\n3,4,5\n").deserialize_array2_padded::<u64>(2).unwrap_err(),
+            NColumns { at_row_index: 1, expected: 2, actual: 3, line: Some(2) }
+        }
+    }
+
+    #[test]
+    fn test_read_with_headers() {
+        for &has_headers in &[false, true] {
+            let (headers, actual) = ReaderBuilder::new()
+                .has_headers(has_headers)
+                .from_reader(Cursor::new("a,b,c\n1,2,3\n4,5,6\n"))
+                .deserialize_array2_with_headers::<u64>(3)
+                .unwrap();
+            assert_eq!(headers, vec!["a", "b", "c"]);
+            assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+        }
+    }
+
+    #[test]
+    fn test_read_with_duplicate_headers() {
+        assert_eq!(
+            in_memory_reader("a,b,a\n1,2,3\n")
+                .deserialize_array2_with_headers::<u64>(3)
+                .unwrap_err(),
+            DuplicateHeader {
+                name: "a".to_string()
+            }
+        );
+        let (headers, _) = in_memory_reader("a,b,c\n1,2,3\n")
+            .deserialize_array2_with_headers::<u64>(3)
+            .unwrap();
+        assert_eq!(headers, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_read_with_wrong_number_of_headers() {
+        assert_matches! {
+            flexible_reader("a,b\n1,2,3\n4,5,6\n")
+                .deserialize_array2_with_headers::<u64>(3)
+                .unwrap_err(),
+            NHeaders { expected: 3, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_columns() {
+        // The unselected column doesn't parse as a number
+        let actual: Array2<u64> = in_memory_reader("x,1,2,3\ny,4,5,6\n")
+            .deserialize_array2_columns(&[3, 1], 2)
+            .unwrap();
+        assert_eq!(actual, array![[3, 1], [6, 4]]);
+    }
+
+    #[test]
+    fn test_read_columns_out_of_order() {
+        let actual: Array2<u64> = test_reader()
+            .deserialize_array2_columns(&[2, 0, 1], 2)
+            .unwrap();
+        assert_eq!(actual.column(0), array![3, 6]);
+        assert_eq!(actual.column(1), array![1, 4]);
+        assert_eq!(actual.column(2), array![2, 5]);
+    }
+
+    #[test]
+    fn test_read_columns_errors() {
+        assert_matches! {
+            test_reader().deserialize_array2_columns::<u64>(&[0, 3], 2).unwrap_err(),
+            NoSuchColumn { at_row_index: 0, column: 3, n_columns: 3 }
+        }
+        assert_matches! {
+            test_reader().deserialize_array2_columns::<u64>(&[0], 3).unwrap_err(),
+            NRows { expected: 3, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_skip() {
+        let actual: Array2<u64> = flexible_reader("instrument x\ndate,2021-01-01,utc\n1,2\n3,4\n")
+            .deserialize_array2_skip(2, (2, 2))
+            .unwrap();
+        assert_eq!(actual, array![[1, 2], [3, 4]]);
+    }
+
+    #[test]
+    fn test_read_with_index() {
+        let (index, actual) = in_memory_reader("2021-01-01,1.0,2.0\n2021-01-02,3.0,4.0\n")
+            .deserialize_array2_with_index::<String, f64>(2)
+            .unwrap();
+        assert_eq!(index, vec!["2021-01-01", "2021-01-02"]);
+        assert_eq!(actual, array![[1.0, 2.0], [3.0, 4.0]]);
+    }
+
+    #[test]
+    fn test_read_with_index_wrong_columns() {
+        assert_matches! {
+            in_memory_reader("2021-01-01,1.0,2.0\n")
+                .deserialize_array2_with_index::<String, f64>(3)
+                .unwrap_err(),
+            NColumns { at_row_index: 0, expected: 3, actual: 2, line: Some(1) }
+        }
+    }
+
+    #[test]
+    fn test_read_split() {
+        let (keys, actual) = in_memory_reader("2021-01-01,7,1.0,2.0\n2021-01-02,8,3.0,4.0\n")
+            .deserialize_array2_split::<(String, u32), f64>(2, 2)
+            .unwrap();
+        assert_eq!(
+            keys,
+            vec![("2021-01-01".to_string(), 7), ("2021-01-02".to_string(), 8)]
+        );
+        assert_eq!(actual, array![[1.0, 2.0], [3.0, 4.0]]);
+
+        assert_eq!(
+            in_memory_reader("2021-01-01,7,1.0,x\n")
+                .deserialize_array2_split::<(String, u32), f64>(2, 2)
+                .unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 3,
+                value: "x".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_f_order() {
+        let actual: Array2<u64> = test_reader().deserialize_array2_f_order((2, 3)).unwrap();
+        assert!(!actual.is_standard_layout());
+        assert!(actual.t().is_standard_layout());
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+    }
+
+    #[test]
+    fn test_read_columns_arrays() {
+        let columns: Vec<Array1<u64>> = test_reader().deserialize_columns((2, 3)).unwrap();
+        assert_eq!(columns, vec![array![1, 4], array![2, 5], array![3, 6]]);
+        assert!(columns.iter().all(|column| column.as_slice().is_some()));
+
+        assert_matches! {
+            test_reader().deserialize_columns::<u64>((3, 3)).unwrap_err(),
+            NRows { expected: 3, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_f_order_wrong_shape() {
+        assert_matches! {
+            test_reader().deserialize_array2_f_order::<u64>((3, 3)).unwrap_err(),
+            NRows { expected: 3, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_fast() {
+        let actual: Array2<f64> = test_reader().deserialize_array2_fast((2, 3)).unwrap();
+        assert_eq!(actual, array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    }
+
+    #[test]
+    fn test_read_fast_from_str_only() {
+        /// A number written in hexadecimal, which only implements `FromStr`
+        #[derive(Debug, PartialEq)]
+        struct Hex(u32);
+
+        impl FromStr for Hex {
+            type Err = std::num::ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                u32::from_str_radix(s, 16).map(Hex)
+            }
+        }
+
+        let actual: Array2<Hex> = in_memory_reader("ff,10\n0,a\n")
+            .deserialize_array2_fast((2, 2))
+            .unwrap();
+        assert_eq!(actual, array![[Hex(255), Hex(16)], [Hex(0), Hex(10)]]);
+
+        assert_eq!(
+            in_memory_reader("ff,g\n")
+                .deserialize_array2_fast::<Hex>((1, 2))
+                .unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 1,
+                value: "g".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_fast_errors() {
+        assert_eq!(
+            in_memory_reader("1,2,3\n4,x,6\n")
+                .deserialize_array2_fast::<i8>((2, 3))
+                .unwrap_err(),
+            Parse {
+                at_row_index: 1,
+                at_col_index: 1,
+                value: "x".to_string()
+            }
+        );
+        assert_eq!(
+            test_reader()
+                .deserialize_array2_fast::<i8>((3, 3))
+                .unwrap_err(),
+            NRows {
+                expected: 3,
+                actual: 2
+            }
+        );
+        assert_eq!(
+            test_reader()
+                .deserialize_array2_fast::<i8>((2, 2))
+                .unwrap_err(),
+            NColumns {
+                at_row_index: 0,
+                expected: 2,
+                actual: 3,
+                line: Some(1),
+            }
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_read_par() {
+        let actual: Array2<u64> = test_reader().deserialize_array2_par((2, 3)).unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_read_par_reports_first_error() {
+        assert_matches! {
+            in_memory_reader("1,2,3\n4,x,6\n7,8,y\n")
+                .deserialize_array2_par::<u64>((3, 3))
+                .unwrap_err(),
+            Parse { at_row_index: 1, at_col_index: 1, .. }
+        }
+    }
+
+    #[test]
+    fn test_read_into() {
+        let mut buffer = Array2::<u64>::zeros((2, 3));
+        test_reader().deserialize_array2_into(&mut buffer).unwrap();
+        assert_eq!(buffer, array![[1, 2, 3], [4, 5, 6]]);
+
+        in_memory_reader("7,8,9\n10,11,12\n")
+            .deserialize_array2_into(&mut buffer)
+            .unwrap();
+        assert_eq!(buffer, array![[7, 8, 9], [10, 11, 12]]);
+    }
+
+    #[test]
+    fn test_read_into_wrong_shape() {
+        assert_matches! {
+            test_reader().deserialize_array2_into(&mut Array2::<i8>::zeros((1, 3))).unwrap_err(),
+            NRows { expected: 1, actual: 2 }
+        }
+        assert_matches! {
+            test_reader().deserialize_array2_into(&mut Array2::<i8>::zeros((2, 2))).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 2, actual: 3, line: Some(1) }
+        }
+    }
+
+    #[test]
+    fn test_read_array1_row() {
+        let actual: Array1<u64> = in_memory_reader("1,2,3\n").deserialize_array1(3).unwrap();
+        assert_eq!(actual, array![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_read_array1_column() {
+        let actual: Array1<u64> = in_memory_reader("1\n2\n3\n")
+            .deserialize_array1_dynamic()
+            .unwrap();
+        assert_eq!(actual, array![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_read_array1_wrong_len() {
+        assert_matches! {
+            in_memory_reader("1,2,3\n").deserialize_array1::<i8>(2).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 2, actual: 3, line: None }
+        }
+        assert_matches! {
+            in_memory_reader("1\n2\n3\n").deserialize_array1::<i8>(4).unwrap_err(),
+            NRows { expected: 4, actual: 3 }
+        }
+    }
+
+    #[test]
+    fn test_read_array1_two_dimensional() {
+        assert_matches! {
+            test_reader().deserialize_array1_dynamic::<i8>().unwrap_err(),
+            NotOneDimensional { n_rows: 2, n_columns: 3 }
+        }
+    }
+
+    #[test]
+    fn test_read_arrayd() {
+        let actual: ArrayD<u64> = in_memory_reader("1,2\n3,4\n5,6\n7,8\n9,10\n11,12\n")
+            .deserialize_arrayd(&[3, 2, 2])
+            .unwrap();
+        let expected = array![[[1, 2], [3, 4]], [[5, 6], [7, 8]], [[9, 10], [11, 12]]].into_dyn();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_arrayd_wrong_shape() {
+        assert_matches! {
+            test_reader().deserialize_arrayd::<u64>(&[2, 2, 3]).unwrap_err(),
+            NRows { expected: 4, actual: 2 }
+        }
+    }
+
+    #[test]
+    fn test_read_arrayd_shape_overflow() {
+        assert_eq!(
+            test_reader()
+                .deserialize_arrayd::<u64>(&[usize::MAX, 2, 3])
+                .unwrap_err(),
+            ShapeOverflow {
+                n_rows: usize::MAX,
+                n_columns: 2
+            }
+        );
+        assert_eq!(
+            test_reader()
+                .deserialize_arrayd::<u64>(&[usize::MAX, 3])
+                .unwrap_err(),
+            ShapeOverflow {
+                n_rows: usize::MAX,
+                n_columns: 3
+            }
+        );
+        assert_eq!(
+            in_memory_reader("")
+                .deserialize_arrayd::<u64>(&[0, usize::MAX, 2])
+                .unwrap_err(),
+            ShapeOverflow {
+                n_rows: 0,
+                n_columns: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_rows() {
+        let mut reader = test_reader();
+        let sums: Vec<u64> = reader
+            .deserialize_rows::<u64>()
+            .map(|row| row.unwrap().sum())
+            .collect();
+        assert_eq!(sums, vec![6, 15]);
+    }
+
+    #[test]
+    fn test_read_rows_wrong_columns() {
+        let mut reader = flexible_reader("1,2\n3,4\n5\n");
+        let rows: Vec<_> = reader.deserialize_rows::<u64>().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].as_ref().unwrap(), array![3, 4]);
+        assert_matches! {
+            rows[2],
+            Err(NColumns { at_row_index: 2, expected: 2, actual: 1, line: Some(3) })
+        }
+    }
+
+    #[test]
+    fn test_read_rows_read() {
+        let mut reader = in_memory_reader("1,2\n3,4\n5,x\n6,7\n");
+        let mut rows = reader.deserialize_rows::<u64>();
+        assert_eq!(rows.rows_read(), 0);
+        assert!(rows.next().unwrap().is_ok());
+        assert!(rows.next().unwrap().is_ok());
+        assert!(rows.next().unwrap().is_err());
+        assert_eq!(rows.rows_read(), 2);
+        assert!(rows.next().unwrap().is_ok());
+        assert_eq!(rows.rows_read(), 3);
+    }
+
+    #[test]
+    fn test_write_ok() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+
+        assert_matches! {
+            writer.serialize_array2(&array![[1, 2, 3], [4, 5, 6]]),
+            Ok(())
+        }
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"1,2,3\n4,5,6\n"
+        );
+    }
+
+    #[test]
+    fn test_write_quote_always() {
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .quote_style(QuoteStyle::Always)
+            .from_writer(vec![]);
+
+        writer
+            .serialize_array2(&array![[1, 2], [3, 4]])
+            .expect("write failed");
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"\"1\",\"2\"\n\"3\",\"4\"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_transposed() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+
+        assert_matches! {
+            writer.serialize_array2(&array![[1, 4], [2, 5], [3, 6]].t().to_owned()),
+            Ok(())
+        }
+
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"1,2,3\n4,5,6\n"
+        );
+    }
+
+    #[test]
+    fn test_write_view() {
+        let array = Array2::from_shape_fn((30, 20), |(i, j)| i * 100 + j);
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+
+        assert_matches! {
+            writer.serialize_array2(&array.slice(s![10..20, 5..15])),
+            Ok(())
+        }
+
+        let expected: String = (10..20)
+            .map(|i| {
+                let row: Vec<String> = (5..15).map(|j| (i * 100 + j).to_string()).collect();
+                row.join(",") + "\n"
+            })
+            .collect();
+        assert_eq!(
+            String::from_utf8(writer.into_inner().expect("flush failed")).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_write_array1_row() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array1(&array![1, 2, 3], Orientation::default())
+            .unwrap();
+
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"1,2,3\n");
+        let actual: Array1<u64> = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(Cursor::new(bytes))
+            .deserialize_array1(3)
+            .unwrap();
+        assert_eq!(actual, array![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_write_array1_column() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array1(&array![1, 2, 3], Orientation::Column)
+            .unwrap();
+
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"1\n2\n3\n");
+        let actual: Array1<u64> = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(Cursor::new(bytes))
+            .deserialize_array1(3)
+            .unwrap();
+        assert_eq!(actual, array![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_write_with_headers() {
+        for &has_headers in &[false, true] {
+            let mut writer = WriterBuilder::new()
+                .has_headers(has_headers)
+                .from_writer(vec![]);
+            writer
+                .serialize_array2_with_headers(&["a", "b", "c"], &array![[1, 2, 3], [4, 5, 6]])
+                .unwrap();
+
+            let bytes = writer.into_inner().expect("flush failed");
+            assert_eq!(bytes, b"a,b,c\n1,2,3\n4,5,6\n");
+
+            let mut reader = ReaderBuilder::new()
+                .has_headers(true)
+                .from_reader(Cursor::new(bytes));
+            assert_eq!(reader.headers().unwrap(), vec!["a", "b", "c"]);
+            let actual: Array2<u64> = reader.deserialize_array2((2, 3)).unwrap();
+            assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+        }
+    }
+
+    #[test]
+    fn test_write_with_wrong_number_of_headers() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        assert_matches! {
+            writer.serialize_array2_with_headers(&["a", "b"], &array![[1, 2, 3]]),
+            Err(WriteError::NHeaders { expected: 3, actual: 2 })
+        }
+    }
+
+    #[test]
+    fn test_path_round_trip() {
+        let path = temp_path("round_trip.csv");
+        let array = array![[1, 2, 3], [4, 5, 6]];
+        write_array2_to_path(&path, &array).unwrap();
+        let actual: Array2<u64> = read_array2_from_path(&path, (2, 3)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[test]
+    fn test_append_to_path() {
+        let path = temp_path("append.csv");
+        append_array2_to_path(&path, &array![[1, 2, 3]]).unwrap();
+        append_array2_to_path(&path, &array![[4, 5, 6], [7, 8, 9]]).unwrap();
+        let actual: Array2<u64> = read_array2_from_path(&path, (3, 3)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
     }
 
+    #[cfg(feature = "num-complex")]
     #[test]
-    fn test_read_too_few_rows() {
+    fn test_read_complex() {
+        let actual: Array2<Complex<f64>> = in_memory_reader("1,-1,0,2.5\n3,0,-4,1\n")
+            .deserialize_array2_complex((2, 2))
+            .unwrap();
+        assert_eq!(
+            actual,
+            array![
+                [Complex::new(1.0, -1.0), Complex::new(0.0, 2.5)],
+                [Complex::new(3.0, 0.0), Complex::new(-4.0, 1.0)]
+            ]
+        );
+
+        // Three fields can't be paired up into complex numbers
         assert_matches! {
-            test_reader().deserialize_array2::<i8>((3, 3)).unwrap_err(),
-            NRows { expected: 3, actual: 2 }
+            test_reader().deserialize_array2_complex::<f64>((2, 1)).unwrap_err(),
+            NColumns { at_row_index: 0, expected: 2, actual: 3, .. }
         }
     }
 
+    #[cfg(feature = "num-complex")]
     #[test]
-    fn test_read_too_many_rows() {
-        assert_matches! {
-            test_reader().deserialize_array2::<i8>((1, 3)).unwrap_err(),
-            NRows { expected: 1, actual: 2 }
+    fn test_complex_round_trip() {
+        let array = array![
+            [Complex::new(1.5, -2.0), Complex::new(0.0, 1.0)],
+            [Complex::new(-3.0, 0.25), Complex::new(4.0, 0.0)]
+        ];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2_complex(&array).unwrap();
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"1.5,-2.0,0.0,1.0\n-3.0,0.25,4.0,0.0\n");
+
+        let actual: Array2<Complex<f64>> = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(Cursor::new(bytes))
+            .deserialize_array2_complex((2, 2))
+            .unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[cfg(feature = "ryu")]
+    #[test]
+    fn test_write_ryu() {
+        let array = array![
+            [0.1, 1.0 / 3.0, -2.5e-7, 1e21],
+            [f64::MAX, f64::MIN_POSITIVE, -0.0, 100.0],
+            [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 5e-324]
+        ];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2_ryu(&array).unwrap();
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(
+            String::from_utf8(bytes.clone()).unwrap(),
+            "0.1,0.3333333333333333,-2.5e-7,1e21\n\
+             1.7976931348623157e308,2.2250738585072014e-308,-0.0,100.0\n\
+             NaN,inf,-inf,5e-324\n"
+        );
+
+        let actual: Array2<f64> = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(Cursor::new(bytes))
+            .deserialize_array2_fast((3, 4))
+            .unwrap();
+        assert!(actual[[2, 0]].is_nan());
+        for (a, b) in actual.iter().zip(array.iter()).filter(|(a, _)| !a.is_nan()) {
+            assert_eq!(a.to_bits(), b.to_bits());
         }
     }
 
+    #[cfg(feature = "memmap2")]
     #[test]
-    fn test_read_too_few_columns() {
-        assert_matches! {
-            test_reader().deserialize_array2::<i8>((2, 4)).unwrap_err(),
-            NColumns { at_row_index: 0, expected: 4, actual: 3 }
+    fn test_read_from_mmap_path() {
+        let path = temp_path("mmap.csv");
+        let array = array![[1.5, 2.0, 3.0], [4.0, 5.0, 6.25]];
+        write_array2_to_path(&path, &array).unwrap();
+        let actual: Array2<f64> = read_array2_from_mmap_path(&path, (2, 3)).unwrap();
+        let expected: Array2<f64> = read_array2_from_path(&path, (2, 3)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(actual, expected);
+        assert_eq!(actual, array);
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn test_read_from_gz_path() {
+        let path = temp_path("read.csv.gz");
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(b"1,2,3\n4,5,6\n").unwrap();
+        encoder.finish().unwrap();
+
+        let actual: Array2<u64> = read_array2_from_gz_path(&path, (2, 3)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn test_gz_path_round_trip() {
+        let path = temp_path("round_trip.csv.gz");
+        let array = array![[1.5, 2.0, 3.0], [4.0, 5.0, 6.25]];
+        write_array2_to_gz_path(&path, &array).unwrap();
+        let actual: Array2<f64> = read_array2_from_gz_path(&path, (2, 3)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_validate_and_reshape() {
+        assert_eq!(
+            validate_and_reshape(vec![1, 2, 3, 4, 5, 6], (2, 3)),
+            Ok(array![[1, 2, 3], [4, 5, 6]])
+        );
+        assert_eq!(
+            validate_and_reshape(vec![1, 2, 3, 4, 5], (2, 3)),
+            Err(ReshapeError::NValues {
+                expected: 6,
+                actual: 5
+            })
+        );
+        assert_eq!(
+            validate_and_reshape(vec![1, 2, 3], (usize::MAX, 2)),
+            Err(ReshapeError::ShapeOverflow {
+                n_rows: usize::MAX,
+                n_columns: 2
+            })
+        );
+        assert_eq!(
+            validate_and_reshape(vec![(); usize::MAX], (usize::MAX, 1)),
+            Err(ReshapeError::ShapeOverflow {
+                n_rows: usize::MAX,
+                n_columns: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        let expected = array![[1, 2, 3], [4, 5, 6]];
+        for &(content, delimiter) in [
+            ("1,2,3\n4,5,6\n", b','),
+            ("1;2;3\n4;5;6\n", b';'),
+            ("1\t2\t3\n4\t5\t6\n", b'\t'),
+        ]
+        .iter()
+        {
+            let (actual, detected) = detect_and_read_array2::<u64, _>(
+                ReaderBuilder::new().has_headers(false),
+                Cursor::new(content),
+                (2, 3),
+            )
+            .unwrap();
+            assert_eq!(actual, expected);
+            assert_eq!(detected, delimiter);
         }
     }
 
     #[test]
-    fn test_read_too_many_columns() {
+    fn test_detect_delimiter_none_match() {
+        let read = detect_and_read_array2::<u64, _>(
+            ReaderBuilder::new().has_headers(false),
+            Cursor::new("1|2|3\n"),
+            (1, 3),
+        );
+        assert_eq!(
+            read.unwrap_err(),
+            Parse {
+                at_row_index: 0,
+                at_col_index: 0,
+                value: "1|2|3".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_from_lines() {
+        let lines = "1,2,3\n% a comment\n4,5,6"
+            .lines()
+            .filter(|line| !line.starts_with('%'))
+            .map(String::from);
+        let actual: Array2<u64> = array2_from_lines(lines, (2, 3)).unwrap();
+        assert_eq!(actual, array![[1, 2, 3], [4, 5, 6]]);
+
+        // A quoted field can still span lines
+        let lines = vec!["\"a".to_string(), "b\",c".to_string()];
+        let actual: Array2<String> = array2_from_lines(lines, (1, 2)).unwrap();
+        assert_eq!(actual, array![["a\nb".to_string(), "c".to_string()]]);
+    }
+
+    #[test]
+    fn test_read_from_str_and_bytes() {
+        let expected = array![[1, 2, 3], [4, 5, 6]];
+        assert_eq!(
+            array2_from_str::<u64>("1,2,3\n4,5,6\n", (2, 3)).unwrap(),
+            expected
+        );
+        assert_eq!(
+            array2_from_bytes::<u64>(b"1,2,3\n4,5,6\n", (2, 3)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_read_missing_path() {
         assert_matches! {
-            test_reader().deserialize_array2::<i8>((2, 2)).unwrap_err(),
-            NColumns { at_row_index: 0, expected: 2, actual: 3 }
+            read_array2_from_path::<u64, _>(temp_path("missing.csv"), (2, 3)).unwrap_err(),
+            Io(_)
         }
     }
 
     #[test]
-    fn test_write_ok() {
+    fn test_write_any_layout() {
+        fn write<S: Data<Elem = i32>>(array: &ArrayBase<S, Ix2>) -> Vec<u8> {
+            let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+            writer.serialize_array2(array).unwrap();
+            writer.into_inner().expect("flush failed")
+        }
+
+        let array = array![[1, 2, 3], [4, 5, 6]];
+        let transposed = array.t();
+        assert!(!transposed.is_standard_layout());
+        assert_eq!(write(&transposed), write(&array.t().to_owned()));
+        assert_eq!(write(&transposed), b"1,4\n2,5\n3,6\n");
+
+        let sliced = transposed.slice(s![..;2, ..]);
+        assert!(!sliced.is_standard_layout());
+        assert_eq!(
+            write(&sliced),
+            write(&array.t().to_owned().slice(s![..;2, ..]).to_owned())
+        );
+        assert_eq!(write(&sliced), b"1,4\n3,6\n");
+    }
+
+    #[test]
+    fn test_write_relayout() {
+        let column_major = Array2::from_shape_vec((2, 3).f(), vec![1, 4, 2, 5, 3, 6]).unwrap();
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2_relayout(&column_major).unwrap();
+        writer
+            .serialize_array2_relayout(&column_major.slice(s![..;-1, ..]))
+            .unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"1,2,3\n4,5,6\n4,5,6\n1,2,3\n"
+        );
+    }
+
+    #[test]
+    fn test_write_with_index() {
         let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_with_index(&["x", "y"], &array![[1, 2, 3], [4, 5, 6]])
+            .unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"x,1,2,3\ny,4,5,6\n"
+        );
+    }
 
+    #[test]
+    fn test_write_with_wrong_index_length() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
         assert_matches! {
-            writer.serialize_array2(&array![[1, 2, 3], [4, 5, 6]]),
-            Ok(())
+            writer.serialize_array2_with_index(&[1], &array![[1, 2, 3], [4, 5, 6]]),
+            Err(WriteError::NIndex { expected: 2, actual: 1 })
+        }
+    }
+
+    #[test]
+    fn test_write_formatted() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_formatted(&array![[1.0, 2.5], [1.0 / 3.0, -4.0]], |x| {
+                format!("{:.2}", x)
+            })
+            .unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"1.00,2.50\n0.33,-4.00\n"
+        );
+
+        // A decimal comma clashes with the delimiter, so the field must be quoted
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_formatted(&array![[1.5, 2.0]], |x| {
+                format!("{:.1}", x).replace('.', ",")
+            })
+            .unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"\"1,5\",\"2,0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_display() {
+        /// A point, which only implements `Display`
+        struct Point(i32, i32);
+
+        impl Display for Point {
+            fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "({}, {})", self.0, self.1)
+            }
+        }
+
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_display(&array![[Point(1, 2), Point(-3, 4)]])
+            .unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"\"(1, 2)\",\"(-3, 4)\"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_bool() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_bool(&array![[true, false], [false, true]], "Y", "N")
+            .unwrap();
+        assert_eq!(writer.into_inner().expect("flush failed"), b"Y,N\nN,Y\n");
+    }
+
+    #[test]
+    fn test_write_row_by_row() {
+        let path = temp_path("row_by_row");
+        {
+            let mut writer = WriterBuilder::new()
+                .has_headers(false)
+                .from_path(&path)
+                .unwrap();
+            for i in 0..3u64 {
+                writer.serialize_row(&array![i, i + 1]).unwrap();
+            }
+            writer.flush().unwrap();
         }
+        let actual: Array2<u64> = read_array2_from_path(&path, (3, 2)).unwrap();
+        assert_eq!(actual, array![[0, 1], [1, 2], [2, 3]]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_write_tuples() {
+        let array = array![[(1.0, 2.0), (3.0, 4.0)], [(5.0, 6.0), (7.0, 8.0)]];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2(&array).unwrap();
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"1.0,2.0,3.0,4.0\n5.0,6.0,7.0,8.0\n");
+
+        // The shape counts elements, not fields
+        let actual: Array2<(f64, f64)> = array2_from_bytes(&bytes, (2, 2)).unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[test]
+    fn test_write_blocks() {
+        let arrays = vec![array![[1, 2, 3], [4, 5, 6]], array![[7, 8]]];
+        let mut builder = WriterBuilder::new();
+        builder.has_headers(false);
+        let bytes = write_array2_blocks(&builder, vec![], &arrays).unwrap();
+        assert_eq!(bytes, b"1,2,3\n4,5,6\n\n7,8\n");
+
+        builder.terminator(Terminator::CRLF);
+        let bytes = write_array2_blocks(&builder, vec![], &arrays).unwrap();
+        assert_eq!(bytes, b"1,2,3\r\n4,5,6\r\n\r\n7,8\r\n");
+
+        let actual: Vec<Array2<u64>> = read_array2_blocks(
+            ReaderBuilder::new().has_headers(false).flexible(true),
+            Cursor::new(bytes),
+        )
+        .unwrap();
+        assert_eq!(actual, arrays);
+    }
+
+    #[test]
+    fn test_write_excel() {
+        let array = array![[1, 2, 3], [4, 5, 6]];
+        let mut builder = WriterBuilder::new();
+        builder.has_headers(false);
+        let bytes = write_array2_excel(&builder, vec![], &array).unwrap();
+        assert_eq!(&bytes[..3], b"\xEF\xBB\xBF");
+        assert_eq!(&bytes[3..], b"1,2,3\n4,5,6\n");
+
+        // The csv reader skips the byte order mark
+        let actual: Array2<u64> = array2_from_bytes(&bytes, (2, 3)).unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[test]
+    fn test_write_no_trailing_newline() {
+        let array = array![[1, 2, 3], [4, 5, 6]];
+        let mut builder = WriterBuilder::new();
+        builder.has_headers(false);
+
+        let mut writer = builder.from_writer(vec![]);
+        writer.serialize_array2(&array).unwrap();
         assert_eq!(
             writer.into_inner().expect("flush failed"),
             b"1,2,3\n4,5,6\n"
         );
+
+        let actual = write_array2_no_trailing_newline(&builder, vec![], &array).unwrap();
+        assert_eq!(actual, b"1,2,3\n4,5,6");
+
+        // The last row is still quoted according to the builder's settings
+        builder.delimiter(b';').terminator(Terminator::CRLF);
+        let actual =
+            write_array2_no_trailing_newline(&builder, vec![], &array![["a", "b"], ["c;d", "e"]])
+                .unwrap();
+        assert_eq!(actual, b"a;b\r\n\"c;d\";e");
     }
 
     #[test]
-    fn test_write_transposed() {
+    fn test_write_sigfigs() {
         let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_sigfigs(
+                &array![
+                    [123456.0, 0.00012345, 1.0, 9.996],
+                    [-0.0015, 0.0, 12.5, f64::NAN]
+                ],
+                3,
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(writer.into_inner().expect("flush failed")).unwrap(),
+            "123000,0.000123,1.00,10.0\n-0.00150,0.00,12.5,NaN\n"
+        );
 
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
         assert_matches! {
-            writer.serialize_array2(&array![[1, 4], [2, 5], [3, 6]].t().to_owned()),
-            Ok(())
+            writer.serialize_array2_sigfigs(&array![[1.0]], 0),
+            Err(WriteError::ZeroSigFigs)
         }
+        assert!(writer.into_inner().expect("flush failed").is_empty());
+    }
 
+    #[test]
+    fn test_write_slice() {
+        let array = array![[1, 2, 3], [4, 5, 6]];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_slice(array.as_slice().unwrap(), array.ncols())
+            .unwrap();
         assert_eq!(
             writer.into_inner().expect("flush failed"),
             b"1,2,3\n4,5,6\n"
         );
+
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        assert_matches! {
+            writer.serialize_array2_slice(&[1, 2, 3, 4, 5], 2),
+            Err(WriteError::SliceLength { len: 5, n_columns: 2 })
+        }
+    }
+
+    #[test]
+    fn test_write_rows_from_iterator() {
+        let rows = (0..3).map(|i| Array1::from(vec![i, i * 10]));
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_rows(rows).unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"0,0\n1,10\n2,20\n"
+        );
+    }
+
+    #[test]
+    fn test_write_rows() {
+        let array = Array::from_shape_fn((5, 2), |(i, j)| i * 2 + j);
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2_rows(&array, 1..3).unwrap();
+        assert_eq!(writer.into_inner().expect("flush failed"), b"2,3\n4,5\n");
+
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        assert_matches! {
+            writer.serialize_array2_rows(&array, 3..6),
+            Err(WriteError::RowRange { start: 3, end: 6, n_rows: 5 })
+        }
+    }
+
+    #[test]
+    fn test_write_with_shape_header() {
+        let array = array![[1, 2, 3], [4, 5, 6]];
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .delimiter(b';')
+            .from_writer(vec![]);
+        writer.serialize_array2_with_shape_header(&array).unwrap();
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"2;3;\n1;2;3\n4;5;6\n");
+
+        let actual: Array2<u64> = ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(b';')
+            .from_reader(Cursor::new(bytes))
+            .deserialize_array2_with_shape_header()
+            .unwrap();
+        assert_eq!(actual, array);
+
+        // A single column gets a single-field header, so neither side needs to be flexible
+        let array = array![[1u64], [2]];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2_with_shape_header(&array).unwrap();
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"2x1\n1\n2\n");
+
+        let actual: Array2<u64> = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(Cursor::new(bytes))
+            .deserialize_array2_with_shape_header()
+            .unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[test]
+    fn test_write_with_trailer() {
+        let array = array![[1, 2, 3], [4, 5, 6]];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2_with_trailer(&array, "#").unwrap();
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"1,2,3\n4,5,6\n# rows=2 cols=3,,\n");
+
+        let actual: Array2<u64> = ReaderBuilder::new()
+            .has_headers(false)
+            .comment(Some(b'#'))
+            .from_reader(&bytes[..])
+            .deserialize_array2_dynamic()
+            .unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[test]
+    fn test_write_strings_round_trip() {
+        let array = array![
+            ["a,b".to_string(), "he said \"hi\"".to_string()],
+            ["line1\nline2".to_string(), "plain".to_string()],
+        ];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2(&array).unwrap();
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(
+            bytes,
+            &b"\"a,b\",\"he said \"\"hi\"\"\"\n\"line1\nline2\",plain\n"[..]
+        );
+
+        let actual: Array2<String> = array2_from_bytes(&bytes, (2, 2)).unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[test]
+    fn test_write_to_string() {
+        assert_eq!(
+            array2_to_string(&array![[1, 2, 3], [4, 5, 6]]).unwrap(),
+            "1,2,3\n4,5,6\n"
+        );
+    }
+
+    #[test]
+    fn test_write_to_bytes() {
+        let array = array![[1.5, -2.0], [f64::NAN, 4.0]];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_array2(&array).unwrap();
+        assert_eq!(
+            array2_to_bytes(&array).unwrap(),
+            writer.into_inner().expect("flush failed")
+        );
+        assert_eq!(array2_to_bytes(&array).unwrap(), b"1.5,-2.0\nNaN,4.0\n");
+    }
+
+    #[test]
+    fn test_write_with_nan_repr() {
+        let array = array![[1.5, f64::NAN], [f64::INFINITY, f64::NEG_INFINITY]];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_with_nan_repr(&array, "NA", "Infinity", "-Infinity")
+            .unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"1.5,NA\nInfinity,-Infinity\n"
+        );
+
+        let array = array![[2.0f32, f32::NAN]];
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .serialize_array2_with_nan_repr(&array, "", "inf", "-inf")
+            .unwrap();
+        assert_eq!(writer.into_inner().expect("flush failed"), b"2.0,\n");
+    }
+
+    #[test]
+    fn test_write_arrayd() {
+        let array = Array::from_shape_fn(vec![2, 3, 2], |index| {
+            index[0] * 100 + index[1] * 10 + index[2]
+        });
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer.serialize_arrayd(&array).unwrap();
+
+        let bytes = writer.into_inner().expect("flush failed");
+        assert_eq!(bytes, b"0,1\n10,11\n20,21\n100,101\n110,111\n120,121\n");
+        let actual: ArrayD<usize> = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(Cursor::new(bytes))
+            .deserialize_arrayd(&[2, 3, 2])
+            .unwrap();
+        assert_eq!(actual, array);
+    }
+
+    #[test]
+    fn test_write_arrayd_too_few_dimensions() {
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        assert_matches! {
+            writer.serialize_arrayd(&array![1, 2, 3].into_dyn()),
+            Err(WriteError::TooFewDimensions { ndim: 1 })
+        }
     }
 
     #[test]
@@ -326,4 +6292,44 @@ mod tests {
             Err(_)
         }
     }
+
+    #[test]
+    fn test_write_error_source() {
+        let destination: &mut [u8] = &mut [0; 8];
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(Cursor::new(destination));
+        let error = writer
+            .serialize_array2(&array![[1, 2, 3], [4, 5, 6]])
+            .unwrap_err();
+        let source = error.source().unwrap();
+        assert_matches!(
+            source.downcast_ref::<csv::Error>().unwrap().kind(),
+            csv::ErrorKind::Io(_)
+        );
+
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        assert!(writer
+            .serialize_array2_with_headers(&["a"], &array![[1, 2]])
+            .unwrap_err()
+            .source()
+            .is_none());
+    }
+
+    #[test]
+    fn test_write_with_default_headers() {
+        // With `has_headers(true)`, csv serializes each row twice, first to infer a header row;
+        // rows of plain values have no header, so only the data is written
+        let mut writer = WriterBuilder::new().from_writer(vec![]);
+        writer
+            .serialize_array2(&array![[1, 2, 3], [4, 5, 6]])
+            .unwrap();
+        writer
+            .serialize_array2_with_index(&["a"], &array![[7, 8]])
+            .unwrap();
+        assert_eq!(
+            writer.into_inner().expect("flush failed"),
+            b"1,2,3\n4,5,6\na,7,8\n"
+        );
+    }
 }